@@ -5,10 +5,12 @@ use axum::{
 };
 use std::sync::Arc;
 use templates::index::index;
+use templates::transform::transform_page;
 
 use db::db_init;
 use messages::handler::{create_message, get_all_messages};
 use messages::repository::MessageRepository;
+use messages::transform::transform;
 use shared::app_metrics::AppMetrics;
 use shared::{AppState, MessageRepositoryTrait};
 
@@ -41,6 +43,8 @@ async fn main() -> Result<(), anyhow::Error> {
             )
             .route("/messages", get(get_all_messages))
             .route("/messages", post(create_message))
+            .route("/transform", get(transform_page))
+            .route("/transform/{op}", post(transform))
             .with_state(app_state);
 
     // Start the server
@@ -1,3 +1,4 @@
 pub mod handler;
 pub mod repository;
 pub mod service;
+pub mod transform;
@@ -10,6 +10,6 @@ impl MessageService {
     pub fn new(db: PgPool) -> MessageService {
         let repo = MessageRepository::new(db.clone());
 
-        return MessageService { repo, db };
+        MessageService { repo, db }
     }
 }
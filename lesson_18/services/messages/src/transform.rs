@@ -0,0 +1,103 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use shared::AppState;
+use std::time::Instant;
+use transformer::{Config, Options, Pipeline, TransformError, ValidationPolicy};
+
+/// Largest output a request may produce, in bytes. Operations that would
+/// build more, like `replace("", ...)` on a large body, are refused before
+/// they run.
+const MAX_OUTPUT: usize = 8 * 1024 * 1024;
+/// Largest value of a numeric argument such as `width` or `max-width`.
+const MAX_NUMBER: usize = 1000;
+
+/// Runs the operation or pipeline `op` on the request body, e.g. text or a
+/// CSV document. The body is always the text itself; `@path` and `-` never
+/// read files or stdin here, and operations that open files are refused.
+pub async fn transform(
+    State(state): State<AppState>,
+    Path(op): Path<String>,
+    body: String,
+) -> (StatusCode, String) {
+    state.metrics.api_calls_total.inc();
+    if let Err(err) = check_numbers(&op) {
+        return (StatusCode::UNPROCESSABLE_ENTITY, error_body(&err));
+    }
+    let start = Instant::now();
+    let result = tokio::task::spawn_blocking(move || {
+        // axum already limits request bodies to 2 MB.
+        let options = Options {
+            config: Config {
+                validation: ValidationPolicy {
+                    max_length: 0,
+                    max_output: MAX_OUTPUT,
+                    ..ValidationPolicy::default()
+                },
+                ..Config::default()
+            },
+            read_sources: false,
+        };
+        transformer::transform(&op, &body, &options)
+    })
+    .await;
+    let duration = start.elapsed().as_secs_f64();
+    state
+        .metrics
+        .request_latency_seconds
+        .with_label_values(&["POST", "/transform/{op}"])
+        .observe(duration);
+
+    match result {
+        Ok(Ok(output)) => (StatusCode::OK, output.text),
+        Ok(Err(err)) => (status_for(&err), error_body(&err)),
+        Err(err) => {
+            eprintln!("Failed to run transformation: {:?}", err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Transformation failed".to_string(),
+            )
+        }
+    }
+}
+
+/// Refuses numeric arguments above [`MAX_NUMBER`]. Pipelines that don't
+/// parse are left to `transform`, which reports them.
+fn check_numbers(op: &str) -> Result<(), TransformError> {
+    let Ok(pipeline) = Pipeline::parse(op) else {
+        return Ok(());
+    };
+    for stage in pipeline.stages() {
+        let Some(operation) = transformer::registry().get(&stage.name) else {
+            continue;
+        };
+        for key in operation.named_args() {
+            if let Ok(Some(value)) = stage.args.named_usize(key) {
+                if value > MAX_NUMBER {
+                    return Err(TransformError::InvalidArgument(format!(
+                        "{}={} is larger than the maximum of {}",
+                        key, value, MAX_NUMBER
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn error_body(err: &TransformError) -> String {
+    format!("Error [{} {}]: {}", err.code(), err.kind(), err)
+}
+
+fn status_for(err: &TransformError) -> StatusCode {
+    match err.root() {
+        TransformError::UnknownOperation { .. } => StatusCode::NOT_FOUND,
+        TransformError::Validation(_) | TransformError::InvalidArgument(_) => {
+            StatusCode::BAD_REQUEST
+        }
+        TransformError::OutputTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+        TransformError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::UNPROCESSABLE_ENTITY,
+    }
+}
@@ -22,6 +22,7 @@ pub async fn index(State(state): State<AppState>) -> Html<String> {
                     (message_table(&messages))
                     h3 { "Send a Message" }
                     (send_message_form())
+                    p { a href="/transform" { "Try the text transformations" } }
                 }
             }
         }
@@ -1,3 +1,5 @@
 pub mod index;
 pub mod message_form;
 pub mod message_table;
+pub mod transform;
+pub mod transform_form;
@@ -0,0 +1,25 @@
+use crate::transform_form::transform_form;
+use axum::{extract::State, response::Html};
+use maud::html;
+use shared::AppState;
+
+pub async fn transform_page(State(state): State<AppState>) -> Html<String> {
+    state.metrics.api_calls_total.inc();
+
+    let page = html! {
+        (maud::DOCTYPE)
+        html {
+            head {
+                meta charset="utf-8";
+                title { "Transform Text" }
+            }
+            body {
+                h3 { "Transform text" }
+                (transform_form(transformer::registry()))
+                a href="/" { "Back to messages" }
+            }
+        }
+    };
+
+    Html(page.into_string())
+}
@@ -0,0 +1,52 @@
+use maud::{html, Markup};
+use transformer::Registry;
+
+pub fn transform_form(registry: &Registry) -> Markup {
+    html! {
+        form id="transform-form" {
+            select id="transform-op" {
+                // The server refuses operations that open files.
+                @for op in registry.operations().filter(|op| !op.opens_files()) {
+                    option value=(op.name()) data-usage=(op.usage()) {
+                        (op.name()) " - " (op.description())
+                    }
+                }
+            }
+            input type="text" id="transform-args" placeholder=r#"Arguments, e.g. ("\s+", "-")"#;
+            p id="transform-usage" {}
+            textarea id="transform-input" rows="10" cols="80" placeholder="Text or CSV to transform..." required {}
+            br;
+            button type="submit" { "Transform" }
+        }
+        pre id="transform-output" {}
+
+        script {
+            (maud::PreEscaped(r#"
+                const select = document.getElementById('transform-op');
+                const showUsage = () => {
+                    const usage = select.selectedOptions[0].dataset.usage;
+                    document.getElementById('transform-usage').textContent = 'Usage: ' + usage;
+                };
+                select.addEventListener('change', showUsage);
+                showUsage();
+
+                document.getElementById('transform-form').addEventListener('submit', async function(e) {
+                    e.preventDefault();
+                    const op = select.value + document.getElementById('transform-args').value.trim();
+                    const output = document.getElementById('transform-output');
+
+                    const response = await fetch('/transform/' + encodeURIComponent(op), {
+                        method: 'POST',
+                        headers: {
+                            'Content-Type': 'text/plain'
+                        },
+                        body: document.getElementById('transform-input').value
+                    });
+
+                    output.textContent = await response.text();
+                    output.style.color = response.ok ? '' : 'red';
+                });
+            "#))
+        }
+    }
+}
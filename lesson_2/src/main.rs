@@ -1,5 +1,6 @@
-use slug::slugify;
+use std::collections::BTreeMap;
 use std::{env, process::exit};
+use transformer::{transform, Config, Options, ValidationPolicy};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -12,22 +13,25 @@ fn main() {
     let op = &args[1];
     let text = &args[2];
 
-    let res = match op.as_str() {
-        "lowercase" => text.to_lowercase(),
-        "upercase" => text.to_uppercase(),
-        "no-spaces" => text.replace(" ", ""),
-        "slugify" => slugify(text),
-        "reverse" => text.chars().rev().collect(),
-        "binary" => text
-            .chars()
-            .map(|c| format!("{:08b}", c as u8))
-            .collect::<Vec<String>>()
-            .join(" "),
-        _ => {
-            eprintln!("Invalid operation: {}", op);
-            exit(1);
-        }
+    // This version never limited the input length, and accepted the
+    // misspelling `upercase`.
+    let options = Options {
+        config: Config {
+            validation: ValidationPolicy {
+                max_length: 0,
+                ..ValidationPolicy::default()
+            },
+            aliases: BTreeMap::from([("upercase".to_string(), "uppercase".to_string())]),
+            ..Config::default()
+        },
+        ..Options::default()
     };
 
-    println!("{}", res);
+    match transform(op, text, &options) {
+        Ok(output) => println!("{}", output.text),
+        Err(err) => {
+            eprintln!("{}", err);
+            exit(1);
+        }
+    }
 }
@@ -1,103 +1,92 @@
-use slug::slugify;
-use std::error::Error;
+use std::io::{self, IsTerminal};
+use std::time::Instant;
 use std::{env, process::exit};
+use transformer::report::{Format, Outcome};
+use transformer::{transform, Config, Options, TransformError};
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    if args.len() < 3 {
-        eprintln!("Invalid input.");
-        exit(1);
-    }
-
-    let op = &args[1];
-    let text = &args[2..].join("");
-
-    let res = match op.as_str() {
-        "lowercase" => to_lower_case(&text),
-        "upercase" => to_upper_case(&text),
-        "no-spaces" => remove_spaces(&text),
-        "slugify" => make_slugify(&text),
-        "reverse" => reverse_string(&text),
-        "binary" => to_binary(&text),
-        "csv" => csv(&text),
-        _ => {
-            eprintln!("Invalid operation: {}", op);
+    let mut config = match Config::load_default() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {}", e);
             exit(1);
         }
     };
+    let mut format = Format::Text;
+    let mut args = env::args().skip(1).peekable();
+    while let Some(flag) = args.next_if(|arg| arg.starts_with("--")) {
+        if flag == "--format" {
+            match args.next().as_deref().map(Format::parse) {
+                Some(Ok(parsed)) => format = parsed,
+                Some(Err(e)) => {
+                    eprintln!("Error: {}", e);
+                    exit(1);
+                }
+                None => {
+                    eprintln!("Error: Missing value for --format");
+                    exit(1);
+                }
+            }
+            continue;
+        }
 
-    match res {
-        Ok(output) => println!("{}", output),
-        Err(err) => {
-            eprintln!("Error: {}", err);
-            exit(1);
+        match config.apply_flag(&flag, &mut args) {
+            Ok(true) => {}
+            Ok(false) => {
+                eprintln!("Unknown flag: {}", flag);
+                exit(1);
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
         }
     }
-}
-
-fn to_lower_case(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-    Ok(input.to_lowercase())
-}
 
-fn to_upper_case(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-    Ok(input.to_uppercase())
-}
+    // Earlier versions accepted this misspelling; keep it working unless the
+    // config defines the name itself.
+    config
+        .aliases
+        .entry("upercase".to_string())
+        .or_insert_with(|| "uppercase".to_string());
 
-fn remove_spaces(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-    Ok(input.replace(" ", ""))
-}
-
-fn make_slugify(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-    Ok(slugify(input))
-}
+    // Tables are plain unless asked otherwise or printed to a terminal.
+    config.style_tables(format == Format::Text && io::stdout().is_terminal());
 
-fn reverse_string(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-    Ok(input.chars().rev().collect())
-}
-
-fn to_binary(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-    Ok(input
-        .chars()
-        .map(|c| format!("{:08b}", c as u8))
-        .collect::<Vec<String>>()
-        .join(" "))
-}
-
-fn csv(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-
-    let mut reader = csv::ReaderBuilder::new()
-        .has_headers(true)
-        .delimiter(b',')
-        .trim(csv::Trim::All)
-        .from_reader(input.as_bytes());
-
-    let mut records = Vec::new();
-    records.push(reader.headers()?.clone());
-    records.extend(reader.records().filter_map(Result::ok));
-
-    let output = records
-        .iter()
-        .map(|record| record.iter().collect::<Vec<&str>>().join(" "))
-        .collect::<Vec<String>>()
-        .join("\n");
+    let args: Vec<String> = args.collect();
+    if args.len() < 2 {
+        eprintln!("Invalid input.");
+        exit(1);
+    }
 
-    Ok(output)
-}
+    let pipeline = &args[0];
+    let text = &args[1..].join("");
 
-fn validate_input(input: &str) -> Result<(), Box<dyn Error>> {
-    if input.is_empty() {
-        return Err(Box::from("String is empty"));
+    // The text may also be `@path` or `-` to read a file or stdin.
+    let options = Options {
+        config,
+        read_sources: true,
+    };
+    let start = Instant::now();
+    let result = transform(pipeline, text, &options).map(|output| output.text);
+    let exit_code = result.as_ref().err().map(TransformError::exit_code);
+
+    if format == Format::Json {
+        let outcome = Outcome {
+            operation: pipeline.trim().to_string(),
+            input: text.to_string(),
+            result,
+            elapsed: start.elapsed(),
+        };
+        println!("{}", outcome.to_json(None));
+    } else {
+        match result {
+            Ok(output) => println!("{}", output),
+            Err(err) => eprintln!("Error [{} {}]: {}", err.code(), err.kind(), err),
+        }
     }
-    if input.len() > 100 {
-        return Err(Box::from("String is too long (max 100 characters)"));
+
+    if let Some(code) = exit_code {
+        exit(code);
     }
-    Ok(())
 }
@@ -0,0 +1,212 @@
+use std::error::Error;
+use std::io::{BufRead, Write};
+use std::time::Instant;
+use transformer::operation::Registry;
+use transformer::pipeline::Pipeline;
+use transformer::report::{Format, Outcome};
+use transformer::source;
+use transformer::validation::ValidationPolicy;
+
+/// Applies `pipeline` to every line of `input`, writing one result per line.
+///
+/// Lines are read one at a time into a reused buffer, so memory use does not
+/// grow with the size of the input. Every line is data, so `@path` or `-`
+/// never read a file or stdin here. Each line is decoded on its own, so a
+/// line that is not valid UTF-8 is read as Latin-1 instead of stopping the
+/// batch. In text mode empty lines are passed
+/// through and lines that fail are reported on stderr and skipped; in JSON
+/// mode every non-empty line produces one object, failures included.
+/// Returns the number of failed lines.
+pub fn run_batch(
+    registry: &Registry,
+    policy: &ValidationPolicy,
+    pipeline: &Pipeline,
+    format: Format,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> Result<usize, Box<dyn Error>> {
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    let mut failures = 0;
+
+    loop {
+        line.clear();
+        if input.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+
+        let end = line
+            .iter()
+            .rposition(|byte| !matches!(byte, b'\n' | b'\r'))
+            .map_or(0, |index| index + 1);
+        if end == 0 {
+            if format == Format::Text {
+                writeln!(output)?;
+            }
+            continue;
+        }
+
+        let start = Instant::now();
+        let (text, result) = match source::decode(line[..end].to_vec()) {
+            Ok(text) => {
+                let result = pipeline.run_text(registry, policy, &text);
+                (text, result)
+            }
+            Err(err) => (String::from_utf8_lossy(&line[..end]).into_owned(), Err(err)),
+        };
+
+        if format == Format::Json {
+            failures += usize::from(result.is_err());
+            let outcome = Outcome {
+                operation: pipeline.to_string(),
+                input: text,
+                result,
+                elapsed: start.elapsed(),
+            };
+            writeln!(output, "{}", outcome.to_json(Some(line_number)))?;
+            continue;
+        }
+
+        match result {
+            Ok(result) => writeln!(output, "{}", result)?,
+            Err(err) => {
+                eprintln!(
+                    "Line {}: [{} {}] {}",
+                    line_number,
+                    err.code(),
+                    err.kind(),
+                    err
+                );
+                failures += 1;
+            }
+        }
+    }
+
+    output.flush()?;
+    Ok(failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use transformer::operations::default_registry;
+
+    #[test]
+    fn test_batch_processes_each_line() {
+        let registry = default_registry();
+        let pipeline = Pipeline::parse("uppercase | reverse").unwrap();
+        let mut output = Vec::new();
+
+        let failures = run_batch(
+            &registry,
+            &ValidationPolicy::default(),
+            &pipeline,
+            Format::Text,
+            Cursor::new("abc\r\n\n  \nxyz"),
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "CBA\n\n  \nZYX\n");
+        assert_eq!(failures, 0);
+    }
+
+    #[test]
+    fn test_batch_lines_are_literal_text() {
+        let registry = default_registry();
+        let pipeline = Pipeline::parse("uppercase").unwrap();
+        let mut output = Vec::new();
+
+        let failures = run_batch(
+            &registry,
+            &ValidationPolicy::default(),
+            &pipeline,
+            Format::Text,
+            Cursor::new(
+                "@john hi
+-
+'quoted'
+",
+            ),
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "@JOHN HI\n-\n'QUOTED'\n"
+        );
+        assert_eq!(failures, 0);
+    }
+
+    #[test]
+    fn test_batch_reads_lines_that_are_not_utf8() {
+        let registry = default_registry();
+        let pipeline = Pipeline::parse("uppercase").unwrap();
+        let mut output = Vec::new();
+
+        let failures = run_batch(
+            &registry,
+            &ValidationPolicy::default(),
+            &pipeline,
+            Format::Text,
+            Cursor::new(b"abc\ncaf\xe9\n\xff\xfe\x00\nxyz\n".to_vec()),
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "ABC\nCAFÉ\nXYZ\n");
+        assert_eq!(failures, 1);
+    }
+
+    #[test]
+    fn test_batch_skips_failing_lines() {
+        let registry = default_registry();
+        let pipeline = Pipeline::parse("from-hex").unwrap();
+        let mut output = Vec::new();
+
+        let failures = run_batch(
+            &registry,
+            &ValidationPolicy::default(),
+            &pipeline,
+            Format::Text,
+            Cursor::new("6869\nzz\n6f6b\n"),
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "hi\nok\n");
+        assert_eq!(failures, 1);
+    }
+
+    #[test]
+    fn test_batch_reports_json_per_line() {
+        let registry = default_registry();
+        let pipeline = Pipeline::parse("from-hex").unwrap();
+        let mut output = Vec::new();
+
+        let failures = run_batch(
+            &registry,
+            &ValidationPolicy::default(),
+            &pipeline,
+            Format::Json,
+            Cursor::new("6869\n\nzz\n"),
+            &mut output,
+        )
+        .unwrap();
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["id"], 1);
+        assert_eq!(lines[0]["output"], "hi");
+        assert_eq!(lines[1]["id"], 3);
+        assert_eq!(lines[1]["error"]["code"], "E006");
+        assert_eq!(failures, 1);
+    }
+}
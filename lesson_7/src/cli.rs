@@ -0,0 +1,225 @@
+use std::error::Error;
+use std::io::{self, IsTerminal};
+use std::num::NonZeroUsize;
+use std::thread;
+use transformer::report::Format;
+use transformer::Config;
+
+pub enum Mode {
+    /// Reads `<operation> <text>` commands from stdin, one per line, and runs
+    /// them on `workers` threads.
+    Interactive { workers: usize, ordered: bool },
+    /// Applies one pipeline to every line of a file or stdin.
+    Batch {
+        pipeline: String,
+        /// Input file, or stdin when `None`.
+        input: Option<String>,
+        /// Output file, or stdout when `None`.
+        output: Option<String>,
+    },
+    /// Renders a CSV file as a table and again whenever it changes.
+    Watch { path: String },
+}
+
+pub struct Options {
+    pub config: Config,
+    pub format: Format,
+    pub mode: Mode,
+}
+
+pub const USAGE: &str = "\
+Usage: lesson_7 [options]
+
+Options:
+  --batch <pipeline>        Apply <pipeline> to every input line instead of reading commands
+  --input <path>            Read batch input from <path> instead of stdin ('-' for stdin)
+  --output <path>           Write batch output to <path> instead of stdout
+  --watch <path>            Render the CSV file at <path> and re-render it when it changes
+  --workers <n>             Number of worker threads for interactive commands
+  --unordered               Print results as soon as they are ready, tagged with a job id
+  --format <text|json>      Print plain results or one JSON object per command
+  --config <path>           Load aliases, defaults and validation rules from a TOML file
+                            instead of ~/.config/transformer/config.toml
+  --max-length <n>          Maximum input length (0 for no limit)
+  --no-limit                Disable all length limits
+  --limit <op>=<n>          Maximum input length for a single operation
+  --count <unit>            Measure length in bytes, chars or graphemes
+  --reject-control          Reject control characters other than tabs and newlines
+  --require-non-whitespace  Reject inputs made only of whitespace
+
+Environment:
+  TABLE_BORDER=ascii        Draw tables with ASCII instead of Unicode borders
+
+Interactive commands are '<operation> [| <operation>...] <input>', where the
+input is literal text, @path to read a file, - to read stdin, or '...' for
+literal text that starts with @ or - ('' for a quote). Lines of batch input
+are always literal text.";
+
+impl Options {
+    pub fn parse(args: impl Iterator<Item = String>) -> Result<Options, Box<dyn Error>> {
+        Options::parse_with(Config::load_default()?, args)
+    }
+
+    /// Parses `args` on top of `config` rather than the config file.
+    fn parse_with(
+        mut config: Config,
+        mut args: impl Iterator<Item = String>,
+    ) -> Result<Options, Box<dyn Error>> {
+        let mut pipeline = None;
+        let mut input = None;
+        let mut output = None;
+        let mut watch = None;
+        let mut workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let mut ordered = true;
+        let mut format = Format::Text;
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for {}", arg))
+            };
+
+            match arg.as_str() {
+                "--batch" => pipeline = Some(value()?),
+                "--input" => input = Some(value()?).filter(|path| path != "-"),
+                "--output" => output = Some(value()?),
+                "--watch" => watch = Some(value()?),
+                "--workers" => {
+                    let value = value()?;
+                    workers = match value.parse::<NonZeroUsize>() {
+                        Ok(workers) => workers.get(),
+                        Err(_) => return Err(format!("Invalid worker count: {}", value).into()),
+                    };
+                }
+                "--unordered" => ordered = false,
+                "--format" => format = Format::parse(&value()?)?,
+                _ => {
+                    if !config.apply_flag(&arg, &mut args)? {
+                        return Err(format!("Unknown argument: {}\n\n{}", arg, USAGE).into());
+                    }
+                }
+            }
+        }
+
+        let mode = match (pipeline, watch) {
+            (Some(_), Some(_)) => return Err(Box::from("--watch can't be combined with --batch")),
+            (Some(pipeline), None) => Mode::Batch {
+                pipeline,
+                input,
+                output,
+            },
+            (None, _) if input.is_some() || output.is_some() => {
+                return Err(Box::from("--input and --output require --batch"))
+            }
+            (None, Some(path)) => Mode::Watch { path },
+            (None, None) => Mode::Interactive { workers, ordered },
+        };
+
+        // Tables are plain unless asked otherwise or printed to a terminal.
+        let to_file = matches!(
+            mode,
+            Mode::Batch {
+                output: Some(_),
+                ..
+            }
+        );
+        config.style_tables(format == Format::Text && !to_file && io::stdout().is_terminal());
+
+        Ok(Options {
+            config,
+            format,
+            mode,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        let args = args.iter().map(|arg| arg.to_string());
+        Options::parse_with(Config::default(), args).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_interactive_is_the_default_mode() {
+        let options = parse(&[]).unwrap();
+
+        assert!(matches!(
+            options.mode,
+            Mode::Interactive { ordered: true, .. }
+        ));
+        assert!(options.format == Format::Text);
+
+        let options = parse(&["--unordered", "--workers", "3", "--format", "json"]).unwrap();
+        assert!(matches!(
+            options.mode,
+            Mode::Interactive {
+                workers: 3,
+                ordered: false
+            }
+        ));
+        assert!(options.format == Format::Json);
+    }
+
+    #[test]
+    fn test_batch_and_watch_modes() {
+        let options = parse(&[
+            "--batch",
+            "uppercase",
+            "--input",
+            "-",
+            "--output",
+            "out.txt",
+        ])
+        .unwrap();
+        let Mode::Batch {
+            pipeline,
+            input,
+            output,
+        } = options.mode
+        else {
+            panic!("expected batch mode");
+        };
+        assert_eq!(pipeline, "uppercase");
+        assert_eq!(input, None);
+        assert_eq!(output.as_deref(), Some("out.txt"));
+
+        let options = parse(&["--watch", "report.csv"]).unwrap();
+        assert!(matches!(options.mode, Mode::Watch { path } if path == "report.csv"));
+    }
+
+    #[test]
+    fn test_config_flags_apply_to_the_config() {
+        let options = parse(&["--max-length", "5", "--reject-control"]).unwrap();
+
+        assert_eq!(options.config.validation.max_length, 5);
+        assert!(options.config.validation.reject_control_chars);
+    }
+
+    #[test]
+    fn test_invalid_arguments_are_reported() {
+        for (args, message) in [
+            (&["--workers", "0"][..], "Invalid worker count: 0"),
+            (&["--batch"], "Missing value for --batch"),
+            (
+                &["--batch", "a", "--watch", "b"],
+                "--watch can't be combined with --batch",
+            ),
+            (
+                &["--output", "out.txt"],
+                "--input and --output require --batch",
+            ),
+            (
+                &["--format", "xml"],
+                "Unknown format: xml (expected text or json)",
+            ),
+        ] {
+            assert_eq!(parse(args).err().unwrap(), message, "{:?}", args);
+        }
+
+        let unknown = parse(&["--nope"]).err().unwrap();
+        assert!(unknown.starts_with("Unknown argument: --nope\n\nUsage: "));
+    }
+}
@@ -0,0 +1,409 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, RwLock};
+use std::thread;
+use std::time::Instant;
+use transformer::error::TransformError;
+use transformer::operation::Registry;
+use transformer::pipeline::Pipeline;
+use transformer::report::{Format, Outcome};
+use transformer::{source, Config};
+
+/// Reads the config again for the `reload` command.
+pub type Reload<'a> = dyn Fn() -> Result<Config, Box<dyn Error>> + Sync + 'a;
+
+struct Job {
+    id: usize,
+    line: String,
+}
+
+type JobResult = (usize, Outcome);
+
+/// Reads commands from stdin and runs them on a pool of `workers` threads.
+///
+/// In ordered mode results are printed in the order the commands were
+/// entered; otherwise each result is printed as soon as it is ready, tagged
+/// with its job id. Returns the number of `csv-validate` commands that found
+/// violations, so scripts can tell whether their data is valid.
+///
+/// The `reload` command replaces `config` with the result of `reload`;
+/// commands that are already running keep the old config.
+pub fn run(
+    registry: &Registry,
+    config: &RwLock<Config>,
+    reload: &Reload<'_>,
+    workers: usize,
+    ordered: bool,
+    format: Format,
+) -> usize {
+    let (job_tx, job_rx) = mpsc::channel();
+    let (result_tx, result_rx) = mpsc::channel();
+    let job_rx = Mutex::new(job_rx);
+
+    thread::scope(|scope| {
+        scope.spawn(move || read_commands(job_tx));
+
+        for _ in 0..workers {
+            let job_rx = &job_rx;
+            let result_tx = result_tx.clone();
+            scope.spawn(move || work(registry, config, reload, job_rx, result_tx));
+        }
+        drop(result_tx);
+
+        match print_results(result_rx, ordered, format, io::stdout().lock()) {
+            Ok(invalid) => invalid,
+            Err(e) => {
+                eprintln!("Failed to write output: {}", e);
+                0
+            }
+        }
+    })
+}
+
+fn read_commands(tx: Sender<Job>) {
+    source::claim_stdin();
+    let stdin = io::stdin();
+    let mut input = String::new();
+    let mut next_id = 1;
+
+    loop {
+        input.clear();
+        match stdin.read_line(&mut input) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("Failed to read input: {}", e);
+                break;
+            }
+        }
+
+        if input.trim().is_empty() {
+            continue;
+        }
+
+        let job = Job {
+            id: next_id,
+            line: input.trim_end().to_string(),
+        };
+        next_id += 1;
+
+        if let Err(e) = tx.send(job) {
+            eprintln!("Failed to send a command: {}", e);
+            break;
+        };
+    }
+}
+
+fn work(
+    registry: &Registry,
+    config: &RwLock<Config>,
+    reload: &Reload<'_>,
+    jobs: &Mutex<Receiver<Job>>,
+    results: Sender<JobResult>,
+) {
+    loop {
+        // The lock is released as soon as a job has been received, so other
+        // workers can pick up the next one while this one runs.
+        let job = jobs.lock().unwrap().recv();
+        let Ok(job) = job else {
+            break;
+        };
+
+        let outcome = execute(registry, config, reload, &job.line);
+        if results.send((job.id, outcome)).is_err() {
+            break;
+        }
+    }
+}
+
+/// Parses and runs one command line. Parse errors are reported like any
+/// other failure, so they reach the output in every format.
+fn execute(
+    registry: &Registry,
+    config: &RwLock<Config>,
+    reload: &Reload<'_>,
+    line: &str,
+) -> Outcome {
+    let start = Instant::now();
+
+    let (operation, input, result) = match Pipeline::parse_line(line) {
+        Ok((pipeline, text)) => {
+            let result = match pipeline.stages() {
+                [stage] if stage.name == "help" => help(registry, &config.read().unwrap(), &text),
+                [stage] if stage.name == "reload" && text.is_empty() => match reload() {
+                    Ok(reloaded) => {
+                        *config.write().unwrap() = reloaded;
+                        Ok("Configuration reloaded".to_string())
+                    }
+                    Err(e) => Err(TransformError::InvalidArgument(e.to_string())),
+                },
+                _ if text.is_empty() => Err(TransformError::InvalidArgument(
+                    "Usage: <operation> [| <operation>...] <text | @path | -> (type 'help' to list operations)"
+                        .to_string(),
+                )),
+                _ => {
+                    // The lock is only held while aliases and defaults are
+                    // expanded, so a reload never waits for a slow command.
+                    let configured = {
+                        let config = config.read().unwrap();
+                        pipeline
+                            .configure(&config)
+                            .map(|pipeline| (pipeline, config.validation.clone()))
+                    };
+                    configured
+                        .map_err(TransformError::InvalidArgument)
+                        .and_then(|(pipeline, policy)| pipeline.run(registry, &policy, &text))
+                }
+            };
+            (pipeline.to_string(), text, result)
+        }
+        Err(e) => (
+            line.trim().to_string(),
+            String::new(),
+            Err(TransformError::InvalidArgument(e)),
+        ),
+    };
+
+    Outcome {
+        operation,
+        input,
+        result,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Lists the operations and aliases, or describes the one named `name`.
+fn help(registry: &Registry, config: &Config, name: &str) -> Result<String, TransformError> {
+    if let Some(pipeline) = config.aliases.get(name) {
+        return Ok(format!("{} - alias for {}", name, pipeline));
+    }
+    if !name.is_empty() {
+        return registry.help_for(name);
+    }
+
+    let mut help = registry.help();
+    if !config.aliases.is_empty() {
+        help.push_str("\nAliases:");
+        for (alias, pipeline) in &config.aliases {
+            help.push_str(&format!("\n  {} = {}", alias, pipeline));
+        }
+    }
+    help.push_str("\nType 'reload' to re-read the configuration.");
+    Ok(help)
+}
+
+/// Writes each result to `output`; in text mode errors go to stderr.
+fn print_results(
+    results: Receiver<JobResult>,
+    ordered: bool,
+    format: Format,
+    mut output: impl Write,
+) -> io::Result<usize> {
+    let mut invalid = 0;
+    let mut print = |id: usize, outcome: Outcome| -> io::Result<()> {
+        invalid += usize::from(matches!(
+            outcome.result.as_ref().map_err(TransformError::root),
+            Err(TransformError::Schema(_))
+        ));
+        if format == Format::Json {
+            return writeln!(output, "{}", outcome.to_json(Some(id)));
+        }
+
+        let tag = if ordered {
+            String::new()
+        } else {
+            format!("[#{}] ", id)
+        };
+        match outcome.result {
+            Ok(text) => writeln!(output, "{}{}", tag, text),
+            Err(err) => {
+                eprintln!("{}Error [{} {}]: {}", tag, err.code(), err.kind(), err);
+                Ok(())
+            }
+        }
+    };
+
+    if !ordered {
+        for (id, outcome) in results {
+            print(id, outcome)?;
+        }
+        return Ok(invalid);
+    }
+
+    let mut pending = BTreeMap::new();
+    let mut next_id = 1;
+    for (id, outcome) in results {
+        pending.insert(id, outcome);
+        while let Some(outcome) = pending.remove(&next_id) {
+            print(next_id, outcome)?;
+            next_id += 1;
+        }
+    }
+    Ok(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn results(jobs: Vec<(usize, Result<&str, TransformError>)>) -> Receiver<JobResult> {
+        let (tx, rx) = mpsc::channel();
+        for (id, result) in jobs {
+            let outcome = Outcome {
+                operation: "uppercase".to_string(),
+                input: "text".to_string(),
+                result: result.map(str::to_string),
+                elapsed: Duration::ZERO,
+            };
+            tx.send((id, outcome)).unwrap();
+        }
+        rx
+    }
+
+    fn no_reload() -> Result<Config, Box<dyn Error>> {
+        Err("not reloadable".into())
+    }
+
+    #[test]
+    fn test_ordered_results_are_printed_in_input_order() {
+        let rx = results(vec![
+            (3, Ok("three")),
+            (1, Ok("one")),
+            (4, Err(TransformError::Schema(Vec::new()))),
+            (2, Ok("two")),
+        ]);
+        let mut output = Vec::new();
+
+        let invalid = print_results(rx, true, Format::Text, &mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "one\ntwo\nthree\n");
+        assert_eq!(invalid, 1);
+    }
+
+    #[test]
+    fn test_unordered_results_are_tagged_with_job_ids() {
+        let rx = results(vec![
+            (2, Ok("two")),
+            (
+                1,
+                Err(TransformError::Validation("Input is empty".to_string())),
+            ),
+            (3, Ok("three")),
+        ]);
+        let mut output = Vec::new();
+
+        let invalid = print_results(rx, false, Format::Text, &mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "[#2] two\n[#3] three\n");
+        assert_eq!(invalid, 0);
+    }
+
+    #[test]
+    fn test_json_results_carry_job_ids() {
+        let rx = results(vec![
+            (2, Ok("two")),
+            (
+                1,
+                Err(TransformError::Validation("Input is empty".to_string())),
+            ),
+        ]);
+        let mut output = Vec::new();
+
+        print_results(rx, true, Format::Json, &mut output).unwrap();
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["id"], 1);
+        assert_eq!(lines[0]["error"]["code"], "E001");
+        assert_eq!(lines[1]["id"], 2);
+        assert_eq!(lines[1]["output"], "two");
+    }
+
+    #[test]
+    fn test_execute_runs_pipelines_with_aliases() {
+        let mut config = Config::default();
+        config
+            .aliases
+            .insert("shout".to_string(), "uppercase | reverse".to_string());
+        let config = RwLock::new(config);
+
+        let outcome = execute(transformer::registry(), &config, &no_reload, "shout abc");
+
+        assert_eq!(outcome.operation, "shout");
+        assert_eq!(outcome.input, "abc");
+        assert_eq!(outcome.result.unwrap(), "CBA");
+
+        let usage = execute(transformer::registry(), &config, &no_reload, "uppercase");
+        assert!(usage.result.unwrap_err().to_string().starts_with("Usage: "));
+    }
+
+    #[test]
+    fn test_help_lists_and_describes_aliases() {
+        let mut config = Config::default();
+        config
+            .aliases
+            .insert("shout".to_string(), "uppercase | reverse".to_string());
+        let config = RwLock::new(config);
+
+        let help = execute(transformer::registry(), &config, &no_reload, "help")
+            .result
+            .unwrap();
+        assert!(help.contains("\nAliases:\n  shout = uppercase | reverse"));
+        assert!(help.ends_with("Type 'reload' to re-read the configuration."));
+
+        let alias = execute(transformer::registry(), &config, &no_reload, "help shout");
+        assert_eq!(
+            alias.result.unwrap(),
+            "shout - alias for uppercase | reverse"
+        );
+
+        let unaliased = execute(
+            transformer::registry(),
+            &RwLock::new(Config::default()),
+            &no_reload,
+            "help",
+        );
+        assert!(!unaliased.result.unwrap().contains("Aliases:"));
+    }
+
+    #[test]
+    fn test_reload_replaces_the_config() {
+        let config = RwLock::new(Config::default());
+        let reload = || {
+            let mut config = Config::default();
+            config
+                .aliases
+                .insert("shout".to_string(), "uppercase".to_string());
+            Ok(config)
+        };
+
+        let outcome = execute(transformer::registry(), &config, &reload, "reload");
+        assert_eq!(outcome.result.unwrap(), "Configuration reloaded");
+        assert_eq!(
+            execute(transformer::registry(), &config, &reload, "shout hi")
+                .result
+                .unwrap(),
+            "HI"
+        );
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_the_config() {
+        let mut config = Config::default();
+        config
+            .aliases
+            .insert("shout".to_string(), "uppercase".to_string());
+        let config = RwLock::new(config);
+
+        let outcome = execute(transformer::registry(), &config, &no_reload, "reload");
+        assert_eq!(outcome.result.unwrap_err().to_string(), "not reloadable");
+        assert!(config.read().unwrap().aliases.contains_key("shout"));
+    }
+}
@@ -1,131 +1,101 @@
-use slug::slugify;
 use std::error::Error;
-use std::io;
-use std::sync::mpsc;
-use std::thread;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::sync::RwLock;
+use std::{env, process::exit};
 
-struct Command {
-    operation: String,
-    text: String,
-}
-
-fn main() {
-    let (tx, rx) = mpsc::channel();
+mod batch;
+mod cli;
+mod interactive;
+mod watch;
 
-    let handle = thread::spawn(move || {
-        let stdin = io::stdin();
-        let mut input = String::new();
+use cli::{Mode, Options};
+use transformer::report::Format;
+use transformer::{source, Config, Pipeline, Registry};
 
-        loop {
-            input.clear();
-            if let Err(e) = stdin.read_line(&mut input) {
-                eprintln!("Failed to read input: {}", e);
-                break;
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match Options::parse(args.iter().cloned()) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    let registry = transformer::registry();
+
+    match options.mode {
+        Mode::Interactive { workers, ordered } => {
+            // Parsing the arguments again re-reads the config file and then
+            // applies the command-line overrides on top of it.
+            let reload = || Options::parse(args.iter().cloned()).map(|options| options.config);
+            let config = RwLock::new(options.config);
+            let invalid =
+                interactive::run(registry, &config, &reload, workers, ordered, options.format);
+            if invalid > 0 {
+                exit(1);
             }
-
-            let args: Vec<String> = input.split_whitespace().map(String::from).collect();
-            if args.is_empty() {
-                continue;
+        }
+        Mode::Batch {
+            pipeline,
+            input,
+            output,
+        } => match run_batch(
+            registry,
+            &options.config,
+            &pipeline,
+            options.format,
+            input,
+            output,
+        ) {
+            Ok(0) => {}
+            Ok(failures) => {
+                eprintln!("{} line(s) failed", failures);
+                exit(1);
             }
-
-            if args.len() < 2 {
-                eprintln!("Usage: <operation> <text>");
-                continue;
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
             }
-
-            let command = Command {
-                operation: args[0].clone(),
-                text: args[1..].join(" "),
-            };
-
-            if let Err(e) = tx.send(command) {
-                eprintln!("Failed to send a command: {}", e);
-                break;
-            };
-        }
-    });
-
-    while let Ok(command) = rx.recv() {
-        let res = match command.operation.as_str() {
-            "lowercase" => to_lower_case(&command.text),
-            "uppercase" => to_upper_case(&command.text),
-            "no-spaces" => remove_spaces(&command.text),
-            "slugify" => make_slugify(&command.text),
-            "reverse" => reverse_string(&command.text),
-            "binary" => to_binary(&command.text),
-            "csv" => csv(&command.text),
-            _ => {
-                eprintln!("Invalid operation: {}", command.operation);
-                continue;
+        },
+        Mode::Watch { path } => {
+            if let Err(e) = watch::run(registry, &options.config, &path, options.format) {
+                eprintln!("Error: {}", e);
+                exit(1);
             }
-        };
-
-        match res {
-            Ok(output) => println!("{}", output),
-            Err(err) => eprintln!("Error: {}", err),
         }
     }
-
-    handle.join().unwrap()
-}
-
-fn to_lower_case(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-    Ok(input.to_lowercase())
-}
-
-fn to_upper_case(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-    Ok(input.to_uppercase())
-}
-
-fn remove_spaces(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-    Ok(input.replace(" ", ""))
-}
-
-fn make_slugify(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-    Ok(slugify(input))
-}
-
-fn reverse_string(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-    Ok(input.chars().rev().collect())
-}
-
-fn to_binary(input: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(input)?;
-    Ok(input
-        .chars()
-        .map(|c| format!("{:08b}", c as u8))
-        .collect::<Vec<String>>()
-        .join(" "))
-}
-
-fn csv(path: &str) -> Result<String, Box<dyn Error>> {
-    validate_input(path)?;
-
-    let mut reader = csv::Reader::from_path(path)?;
-
-    let mut records = Vec::new();
-    let headers = reader.headers()?;
-    records.push(headers.iter().collect::<Vec<&str>>().join(" "));
-
-    for result in reader.records() {
-        let record = result?;
-        records.push(record.iter().collect::<Vec<&str>>().join(" "));
-    }
-
-    Ok(records.join("\n"))
 }
 
-fn validate_input(input: &str) -> Result<(), Box<dyn Error>> {
-    if input.is_empty() {
-        return Err(Box::from("String is empty"));
-    }
-    if input.len() > 100 {
-        return Err(Box::from("String is too long (max 100 characters)"));
-    }
-    Ok(())
+fn run_batch(
+    registry: &Registry,
+    config: &Config,
+    pipeline: &str,
+    format: Format,
+    input: Option<String>,
+    output: Option<String>,
+) -> Result<usize, Box<dyn Error>> {
+    let pipeline = Pipeline::parse(pipeline)?.configure(config)?;
+
+    let input: Box<dyn io::BufRead> = match input {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => {
+            source::claim_stdin();
+            Box::new(io::stdin().lock())
+        }
+    };
+    let output: Box<dyn Write> = match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    batch::run_batch(
+        registry,
+        &config.validation,
+        &pipeline,
+        format,
+        input,
+        output,
+    )
 }
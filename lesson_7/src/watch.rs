@@ -0,0 +1,118 @@
+use notify::RecursiveMode;
+use notify_debouncer_mini::new_debouncer;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime};
+use transformer::report::{Format, Outcome};
+use transformer::{Config, Pipeline, Registry};
+
+/// How long the file has to stay unchanged before it is rendered again, so
+/// a report that is written in several steps is only parsed once.
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Renders the CSV file at `path` as a table, and again whenever it changes,
+/// until the process is interrupted.
+///
+/// The directory is watched rather than the file, so files that are
+/// replaced instead of rewritten, as editors and many generators do, keep
+/// being followed. Parse errors are shown in place of the table. The
+/// defaults in `config` apply, e.g. the CSV delimiter.
+pub fn run(
+    registry: &Registry,
+    config: &Config,
+    path: &str,
+    format: Format,
+) -> Result<(), Box<dyn Error>> {
+    let pipeline = Pipeline::parse("csv")?.configure(config)?;
+    let render = || render(registry, config, &pipeline, path, format);
+
+    let file = Path::new(path);
+    let name = file
+        .file_name()
+        .ok_or_else(|| format!("Not a file: {}", path))?;
+    let dir = match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => fs::canonicalize(dir)?,
+        _ => fs::canonicalize(".")?,
+    };
+    let watched = dir.join(name);
+
+    let (tx, rx) = mpsc::channel();
+    let mut debouncer = new_debouncer(DEBOUNCE, tx)?;
+    debouncer
+        .watcher()
+        .watch(&dir, RecursiveMode::NonRecursive)?;
+
+    let mut rendered = version(&watched);
+    render();
+    for result in rx {
+        match result {
+            Ok(events) if events.iter().any(|event| event.path == watched) => {
+                // Reading the file raises events too; only render new content.
+                let current = version(&watched);
+                if current != rendered {
+                    rendered = current;
+                    render();
+                }
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Failed to watch {}: {}", path, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Identifies a version of the file by its modification time and size, or
+/// `None` while it doesn't exist.
+fn version(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn render(registry: &Registry, config: &Config, pipeline: &Pipeline, path: &str, format: Format) {
+    let start = Instant::now();
+    let result = pipeline.run(registry, &config.validation, &format!("@{}", path));
+    let outcome = Outcome {
+        operation: pipeline.to_string(),
+        input: path.to_string(),
+        result,
+        elapsed: start.elapsed(),
+    };
+
+    if format == Format::Json {
+        println!("{}", outcome.to_json(None));
+        return;
+    }
+
+    // Clears the screen and moves the cursor to the top-left corner.
+    print!("\x1B[2J\x1B[H");
+    println!("Watching {} (Ctrl+C to stop)\n", path);
+    match outcome.result {
+        Ok(output) => println!("{}", output),
+        Err(err) => println!("Error [{} {}]: {}", err.code(), err.kind(), err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+
+    #[test]
+    fn test_version_changes_with_the_file() {
+        let path = env::temp_dir().join(format!("lesson_7-watch-{}.csv", process::id()));
+        assert_eq!(version(&path), None);
+
+        fs::write(&path, "a,b\n1,2\n").unwrap();
+        let written = version(&path);
+        assert!(written.is_some());
+
+        fs::write(&path, "a,b\n1,2\n3,4\n").unwrap();
+        assert_ne!(version(&path), written);
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(version(&path), None);
+    }
+}
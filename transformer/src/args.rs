@@ -0,0 +1,266 @@
+use crate::error::TransformError;
+use std::fmt;
+
+/// Arguments given to an operation in parentheses right after its name, e.g.
+/// `replace("(\w+)@(\w+)", "$2 at $1", limit=1)`.
+///
+/// Values are separated by commas. Quote a value with `"` or `'` to keep
+/// commas, parentheses or surrounding spaces; inside quotes a doubled quote
+/// stands for a literal one and backslashes are kept as they are. Unquoted
+/// `key=value` items are named arguments.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Args {
+    positional: Vec<String>,
+    named: Vec<(String, String)>,
+}
+
+impl Args {
+    /// Parses the argument list at the start of `text`, which must start
+    /// with `(`. Returns the arguments and the text after the closing `)`.
+    pub fn parse(text: &str) -> Result<(Args, &str), String> {
+        let mut args = Args::default();
+        let mut rest = text
+            .strip_prefix('(')
+            .ok_or("Expected '(' before arguments")?
+            .trim_start();
+
+        if let Some(after) = rest.strip_prefix(')') {
+            return Ok((args, after));
+        }
+
+        loop {
+            let key_end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                .unwrap_or(rest.len());
+            let key = match rest[key_end..].trim_start().strip_prefix('=') {
+                Some(value) if key_end > 0 => {
+                    let key = &rest[..key_end];
+                    rest = value.trim_start();
+                    Some(key)
+                }
+                _ => None,
+            };
+
+            let (value, after) = parse_value(rest)?;
+            match key {
+                Some(key) if args.named(key).is_some() => {
+                    return Err(format!("Duplicate argument: {}", key))
+                }
+                Some(key) => args.named.push((key.to_string(), value)),
+                None => args.positional.push(value),
+            }
+
+            rest = after.trim_start();
+            match rest.chars().next() {
+                Some(',') => rest = rest[1..].trim_start(),
+                Some(')') => return Ok((args, &rest[1..])),
+                Some(c) => return Err(format!("Expected ',' or ')' in arguments, found '{}'", c)),
+                None => return Err("Missing ')' after arguments".to_string()),
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positional.is_empty() && self.named.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.positional.get(index).map(String::as_str)
+    }
+
+    /// The positional argument at `index`, described as `name` when missing.
+    pub fn required(&self, index: usize, name: &str) -> Result<&str, TransformError> {
+        self.get(index)
+            .ok_or_else(|| TransformError::InvalidArgument(format!("Missing argument: {}", name)))
+    }
+
+    pub fn named(&self, key: &str) -> Option<&str> {
+        self.named
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the named argument `key` unless it was given explicitly.
+    pub fn insert_default(&mut self, key: &str, value: &str) {
+        if self.named(key).is_none() {
+            self.named.push((key.to_string(), value.to_string()));
+        }
+    }
+
+    pub fn named_usize(&self, key: &str) -> Result<Option<usize>, TransformError> {
+        self.named(key)
+            .map(|value| {
+                value.parse().map_err(|_| {
+                    TransformError::InvalidArgument(format!(
+                        "Invalid value for {}: {} (expected a number)",
+                        key, value
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// A named on/off switch; missing means off.
+    pub fn named_bool(&self, key: &str) -> Result<bool, TransformError> {
+        match self.named(key) {
+            None | Some("false" | "no") => Ok(false),
+            Some("true" | "yes") => Ok(true),
+            Some(value) => Err(TransformError::InvalidArgument(format!(
+                "Invalid value for {}: {} (expected true or false)",
+                key, value
+            ))),
+        }
+    }
+
+    /// Checks that `operation` got at most `positional` positional
+    /// arguments and no named arguments other than `named`.
+    pub fn expect(
+        &self,
+        operation: &str,
+        positional: usize,
+        named: &[&str],
+    ) -> Result<(), TransformError> {
+        if positional == 0 && named.is_empty() && !self.is_empty() {
+            return Err(TransformError::InvalidArgument(format!(
+                "{} takes no arguments",
+                operation
+            )));
+        }
+        if self.positional.len() > positional {
+            return Err(TransformError::InvalidArgument(format!(
+                "{} takes at most {} argument(s), got {}",
+                operation,
+                positional,
+                self.positional.len()
+            )));
+        }
+        match self
+            .named
+            .iter()
+            .find(|(key, _)| !named.contains(&key.as_str()))
+        {
+            Some((key, _)) => Err(TransformError::InvalidArgument(format!(
+                "Unknown argument for {}: {}",
+                operation, key
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writes the arguments back in the syntax [`Args::parse`] accepts.
+impl fmt::Display for Args {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let quote = |value: &str| {
+            let plain = !value.is_empty()
+                && value
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+            match plain {
+                true => value.to_string(),
+                false => format!("\"{}\"", value.replace('"', "\"\"")),
+            }
+        };
+
+        let items: Vec<String> = self
+            .positional
+            .iter()
+            .map(|value| quote(value))
+            .chain(
+                self.named
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, quote(value))),
+            )
+            .collect();
+        write!(f, "({})", items.join(", "))
+    }
+}
+
+/// Reads one quoted or bare value from the start of `text`.
+fn parse_value(text: &str) -> Result<(String, &str), String> {
+    let quote = match text.chars().next() {
+        Some(quote @ ('"' | '\'')) => quote,
+        _ => {
+            let end = text.find([',', ')']).unwrap_or(text.len());
+            return Ok((text[..end].trim_end().to_string(), &text[end..]));
+        }
+    };
+
+    let mut value = String::new();
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != quote {
+            value.push(c);
+        } else if chars.next_if(|&(_, next)| next == quote).is_some() {
+            value.push(quote);
+        } else {
+            return Ok((value, &text[i + 1..]));
+        }
+    }
+
+    Err(format!("Unclosed {} in arguments", quote))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_positional_and_named() {
+        let (args, rest) = Args::parse(r#"("(\w+), (\w+)", '$2 ''$1''', limit = 2) text"#).unwrap();
+
+        assert_eq!(args.get(0), Some(r"(\w+), (\w+)"));
+        assert_eq!(args.get(1), Some("$2 '$1'"));
+        assert_eq!(args.named("limit"), Some("2"));
+        assert_eq!(rest, " text");
+    }
+
+    #[test]
+    fn test_parse_bare_values_and_empty_list() {
+        let (args, _) = Args::parse("( a b ,\\d+)").unwrap();
+        assert_eq!(args.get(0), Some("a b"));
+        assert_eq!(args.get(1), Some("\\d+"));
+
+        let (args, rest) = Args::parse("()x").unwrap();
+        assert!(args.is_empty());
+        assert_eq!(rest, "x");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Args::parse("(\"abc").unwrap_err(),
+            "Unclosed \" in arguments"
+        );
+        assert_eq!(
+            Args::parse("(a, b").unwrap_err(),
+            "Missing ')' after arguments"
+        );
+        assert_eq!(
+            Args::parse("(\"a\" b)").unwrap_err(),
+            "Expected ',' or ')' in arguments, found 'b'"
+        );
+        assert_eq!(
+            Args::parse("(n=1, n=2)").unwrap_err(),
+            "Duplicate argument: n"
+        );
+    }
+
+    #[test]
+    fn test_expect_and_display() {
+        let (args, _) = Args::parse("(x, \"a,b\", limit=3)").unwrap();
+
+        assert!(args.expect("split", 2, &["limit"]).is_ok());
+        assert_eq!(
+            args.expect("split", 1, &["limit"]).unwrap_err().to_string(),
+            "split takes at most 1 argument(s), got 2"
+        );
+        assert_eq!(
+            args.expect("split", 2, &[]).unwrap_err().to_string(),
+            "Unknown argument for split: limit"
+        );
+        assert_eq!(args.to_string(), "(x, \"a,b\", limit=3)");
+        assert_eq!(args.named_usize("limit").unwrap(), Some(3));
+    }
+}
@@ -0,0 +1,310 @@
+use crate::pipeline::Pipeline;
+use crate::validation::ValidationPolicy;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Settings read from a TOML file such as
+///
+/// ```toml
+/// [aliases]
+/// id = "lowercase | no-spaces"
+///
+/// [defaults.csv]
+/// delimiter = ";"
+/// max_length = 0
+///
+/// [validation]
+/// max_length = 500
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub validation: ValidationPolicy,
+    /// Names that stand for a pipeline, keyed by name.
+    pub aliases: BTreeMap<String, String>,
+    /// Named arguments used when an operation is run without them, keyed by
+    /// operation. Only arguments the operation accepts are allowed; a
+    /// `max_length` default becomes a limit in `validation` instead.
+    pub defaults: BTreeMap<String, BTreeMap<String, String>>,
+    /// Named arguments for the last stage of a pipeline, used when its
+    /// operation accepts them and neither the stage nor `defaults` set them.
+    /// Not read from the file; see [`Config::style_tables`].
+    pub output_defaults: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    validation: ValidationPolicy,
+    #[serde(default)]
+    aliases: BTreeMap<String, String>,
+    #[serde(default)]
+    defaults: BTreeMap<String, toml::Table>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/transformer/config.toml`, falling back to
+    /// `~/.config` when `XDG_CONFIG_HOME` is not set.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(base.join("transformer").join("config.toml"))
+    }
+
+    /// Reads the config at [`Config::default_path`], or returns the default
+    /// config if there is no such file.
+    pub fn load_default() -> Result<Config, Box<dyn Error>> {
+        match Config::default_path() {
+            Some(path) if path.exists() => Config::load(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Config, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config '{}': {}", path.display(), e))?;
+        Config::parse(&contents)
+            .map_err(|e| format!("Invalid config '{}': {}", path.display(), e).into())
+    }
+
+    pub fn parse(contents: &str) -> Result<Config, String> {
+        let file: ConfigFile = toml::from_str(contents).map_err(|e| e.to_string())?;
+        let mut config = Config {
+            validation: file.validation,
+            aliases: file.aliases,
+            defaults: BTreeMap::new(),
+            output_defaults: BTreeMap::new(),
+        };
+
+        for (name, spec) in &config.aliases {
+            Pipeline::parse(spec).map_err(|e| format!("Invalid alias '{}': {}", name, e))?;
+        }
+
+        for (operation, table) in file.defaults {
+            let accepted = crate::registry()
+                .find(&operation)
+                .map_err(|e| e.to_string())?
+                .named_args();
+            let mut defaults = BTreeMap::new();
+            for (key, value) in table {
+                if key != "max_length" && !accepted.contains(&key.as_str()) {
+                    return Err(format!(
+                        "Unknown default for {}: {} (expected {})",
+                        operation,
+                        key,
+                        [accepted, &["max_length"]].concat().join(", ")
+                    ));
+                }
+
+                let value = match value {
+                    toml::Value::String(value) => value,
+                    toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_) => {
+                        value.to_string()
+                    }
+                    _ => {
+                        return Err(format!(
+                            "Default {} for {} must be a string, number or boolean",
+                            key, operation
+                        ))
+                    }
+                };
+
+                if key == "max_length" {
+                    let limit = value
+                        .parse()
+                        .map_err(|_| format!("Invalid max_length for {}: {}", operation, value))?;
+                    config.validation.limits.insert(operation.clone(), limit);
+                } else {
+                    defaults.insert(key, value);
+                }
+            }
+            config.defaults.insert(operation, defaults);
+        }
+
+        Ok(config)
+    }
+
+    /// Sets the default of the named argument `key` for every operation
+    /// that accepts it, unless the config already gives one.
+    pub fn insert_default(&mut self, key: &str, value: &str) {
+        for operation in crate::registry().operations() {
+            if operation.named_args().contains(&key) {
+                self.defaults
+                    .entry(operation.name().to_string())
+                    .or_default()
+                    .entry(key.to_string())
+                    .or_insert_with(|| value.to_string());
+            }
+        }
+    }
+
+    /// Styles the tables printed by the last stage of a pipeline: ASCII
+    /// borders when `TABLE_BORDER=ascii`, and bold headers when the output
+    /// goes to a `terminal`. Earlier stages always render plain tables, so
+    /// the next stage never sees escape codes and results do not depend on
+    /// where they are printed.
+    pub fn style_tables(&mut self, terminal: bool) {
+        if env::var("TABLE_BORDER").is_ok_and(|value| value.eq_ignore_ascii_case("ascii")) {
+            self.output_defaults
+                .insert("border".to_string(), "ascii".to_string());
+        }
+        if terminal {
+            self.output_defaults
+                .insert("bold".to_string(), "true".to_string());
+        }
+    }
+
+    /// Applies a command-line flag like [`ValidationPolicy::apply_flag`].
+    ///
+    /// `--config <path>` replaces the whole config, so flags given after it
+    /// override the file.
+    pub fn apply_flag(
+        &mut self,
+        flag: &str,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<bool, Box<dyn Error>> {
+        if flag != "--config" {
+            return self.validation.apply_flag(flag, args);
+        }
+
+        let path = args.next().ok_or("Missing value for --config")?;
+        *self = Config::load(Path::new(&path))?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::LengthUnit;
+
+    const CONFIG: &str = r#"
+        [aliases]
+        id = "lowercase | no-spaces"
+        reverse = "nfc | reverse"
+        shout = "id | uppercase"
+
+        [defaults.csv]
+        delimiter = ";"
+        max_length = 0
+
+        [defaults.wrap]
+        width = 20
+
+        [validation]
+        max_length = 500
+        unit = "chars"
+    "#;
+
+    fn configure(pipeline: &str) -> String {
+        let config = Config::parse(CONFIG).unwrap();
+        Pipeline::parse(pipeline)
+            .unwrap()
+            .configure(&config)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_config_file_format() {
+        let config = Config::parse(CONFIG).unwrap();
+
+        assert_eq!(config.validation.max_length, 500);
+        assert_eq!(config.validation.unit, LengthUnit::Chars);
+        assert_eq!(config.validation.limits["csv"], 0);
+        assert_eq!(config.defaults["csv"]["delimiter"], ";");
+        assert_eq!(config.defaults["wrap"]["width"], "20");
+        assert!(!config.defaults["csv"].contains_key("max_length"));
+    }
+
+    #[test]
+    fn test_aliases_expand_like_shell_aliases() {
+        assert_eq!(configure("id | binary"), "lowercase | no-spaces | binary");
+        assert_eq!(configure("reverse"), "nfc | reverse");
+        assert_eq!(configure("shout"), "lowercase | no-spaces | uppercase");
+    }
+
+    #[test]
+    fn test_defaults_fill_in_missing_arguments() {
+        assert_eq!(configure("wrap"), "wrap(width=20)");
+        assert_eq!(configure("wrap(width=5)"), "wrap(width=5)");
+        assert_eq!(
+            configure("wrap(align=right)"),
+            "wrap(align=right, width=20)"
+        );
+    }
+
+    #[test]
+    fn test_insert_default_keeps_configured_values() {
+        let mut config = Config::parse("[defaults.csv]\nborder = \"unicode\"").unwrap();
+        config.insert_default("border", "ascii");
+
+        assert_eq!(config.defaults["csv"]["border"], "unicode");
+        assert_eq!(config.defaults["stats"]["border"], "ascii");
+        assert!(!config.defaults.contains_key("wrap"));
+    }
+
+    #[test]
+    fn test_output_defaults_only_style_the_last_stage() {
+        let mut config = Config::parse("[defaults.csv]\nborder = \"unicode\"").unwrap();
+        config.output_defaults = BTreeMap::from([
+            ("bold".to_string(), "true".to_string()),
+            ("border".to_string(), "ascii".to_string()),
+        ]);
+        let configure = |spec: &str| {
+            Pipeline::parse(spec)
+                .unwrap()
+                .configure(&config)
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            configure("csv | csv"),
+            "csv(border=unicode) | csv(border=unicode, bold=true)"
+        );
+        assert_eq!(configure("stats | sha256"), "stats | sha256");
+        assert_eq!(
+            configure("uppercase | stats(bold=false)"),
+            "uppercase | stats(bold=false, border=ascii)"
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert_eq!(
+            Config::parse("[aliases]\nbad = \"lowercase |\"").unwrap_err(),
+            "Invalid alias 'bad': Missing operation name in pipeline"
+        );
+        assert_eq!(
+            Config::parse("[defaults.csv]\nmax_length = \"lots\"").unwrap_err(),
+            "Invalid max_length for csv: lots"
+        );
+        assert_eq!(
+            Config::parse("[defaults.csv-qurey]\nlimit = 5").unwrap_err(),
+            "Invalid operation: csv-qurey (did you mean 'csv-query'?)"
+        );
+        assert_eq!(
+            Config::parse("[defaults.csv-query]\nseparator = \";\"").unwrap_err(),
+            "Unknown default for csv-query: separator \
+             (expected delimiter, border, bold, max-width, max_length)"
+        );
+        for misspelled in [
+            "[default.csv]\ndelimiter = \";\"",
+            "[alias]\nup = \"uppercase\"",
+        ] {
+            let err = Config::parse(misspelled).unwrap_err();
+            assert!(err.contains("unknown field"), "{}", err);
+        }
+        assert_eq!(
+            Config::parse("[defaults.lowercase]\nwidth = 20").unwrap_err(),
+            "Unknown default for lowercase: width (expected max_length)"
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Everything that can go wrong while running an operation.
+#[derive(Debug)]
+pub enum TransformError {
+    /// The input was rejected by the validation policy.
+    Validation(String),
+    UnknownOperation {
+        name: String,
+        suggestion: Option<String>,
+    },
+    /// The operation's arguments could not be parsed or don't fit the data,
+    /// e.g. a malformed query or an unknown column.
+    InvalidArgument(String),
+    Io(io::Error),
+    /// A CSV file could not be parsed. Lines and fields are 1-based.
+    Csv {
+        line: Option<u64>,
+        field: Option<u64>,
+        message: String,
+    },
+    /// Encoded input could not be decoded; `offset` is the failing byte.
+    Encoding {
+        offset: usize,
+        message: String,
+    },
+    /// A JSON document could not be parsed or produced. Lines and columns
+    /// are 1-based, `0` when serde_json has no location.
+    Json {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Rows of a CSV file broke the rules of a schema.
+    Schema(Vec<Violation>),
+    /// An operation would produce more than
+    /// [`ValidationPolicy::max_output`](crate::ValidationPolicy::max_output)
+    /// bytes.
+    OutputTooLarge {
+        limit: usize,
+    },
+    /// A pipeline stage failed; `index` is 1-based.
+    Stage {
+        index: usize,
+        operation: String,
+        source: Box<TransformError>,
+    },
+}
+
+/// One broken schema rule. `column` is `None` for problems with a whole
+/// row, such as a wrong number of fields.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Violation {
+    pub line: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    pub reason: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.column {
+            Some(column) => write!(
+                f,
+                "line {}, column '{}': {}",
+                self.line, column, self.reason
+            ),
+            None => write!(f, "line {}: {}", self.line, self.reason),
+        }
+    }
+}
+
+impl TransformError {
+    /// The innermost error, looking through pipeline stages.
+    pub fn root(&self) -> &TransformError {
+        match self {
+            TransformError::Stage { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// Short machine-readable name of the error kind.
+    pub fn kind(&self) -> &'static str {
+        match self.root() {
+            TransformError::Validation(_) => "validation",
+            TransformError::UnknownOperation { .. } => "unknown_operation",
+            TransformError::InvalidArgument(_) => "invalid_argument",
+            TransformError::Io(_) => "io",
+            TransformError::Csv { .. } => "csv",
+            TransformError::Encoding { .. } => "encoding",
+            TransformError::Json { .. } => "json",
+            TransformError::Schema(_) => "schema",
+            TransformError::OutputTooLarge { .. } => "output_too_large",
+            TransformError::Stage { .. } => unreachable!("root() never returns a stage"),
+        }
+    }
+
+    /// Stable error code. Codes are never reused or renumbered.
+    pub fn code(&self) -> &'static str {
+        match self.root() {
+            TransformError::Validation(_) => "E001",
+            TransformError::UnknownOperation { .. } => "E002",
+            TransformError::InvalidArgument(_) => "E003",
+            TransformError::Io(_) => "E004",
+            TransformError::Csv { .. } => "E005",
+            TransformError::Encoding { .. } => "E006",
+            TransformError::Json { .. } => "E007",
+            TransformError::Schema(_) => "E008",
+            TransformError::OutputTooLarge { .. } => "E009",
+            TransformError::Stage { .. } => unreachable!("root() never returns a stage"),
+        }
+    }
+
+    /// Process exit code for the error kind. `1` is left for usage errors.
+    pub fn exit_code(&self) -> i32 {
+        match self.root() {
+            TransformError::Validation(_) => 2,
+            TransformError::UnknownOperation { .. } => 3,
+            TransformError::InvalidArgument(_) => 4,
+            TransformError::Io(_) => 5,
+            TransformError::Csv { .. } => 6,
+            TransformError::Encoding { .. } => 7,
+            TransformError::Json { .. } => 8,
+            TransformError::Schema(_) => 9,
+            TransformError::OutputTooLarge { .. } => 10,
+            TransformError::Stage { .. } => unreachable!("root() never returns a stage"),
+        }
+    }
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformError::Validation(message) | TransformError::InvalidArgument(message) => {
+                write!(f, "{}", message)
+            }
+            TransformError::UnknownOperation {
+                name,
+                suggestion: Some(suggestion),
+            } => write!(
+                f,
+                "Invalid operation: {} (did you mean '{}'?)",
+                name, suggestion
+            ),
+            TransformError::UnknownOperation { name, .. } => {
+                write!(f, "Invalid operation: {}", name)
+            }
+            TransformError::Io(err) => write!(f, "I/O error: {}", err),
+            TransformError::Csv {
+                line,
+                field,
+                message,
+            } => {
+                write!(f, "CSV error")?;
+                if let Some(line) = line {
+                    write!(f, " on line {}", line)?;
+                }
+                if let Some(field) = field {
+                    write!(f, ", field {}", field)?;
+                }
+                write!(f, ": {}", message)
+            }
+            TransformError::Encoding { offset, message } => {
+                write!(f, "{} at byte offset {}", message, offset)
+            }
+            TransformError::Json {
+                line: 0, message, ..
+            } => write!(f, "JSON error: {}", message),
+            TransformError::Json {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "JSON error on line {}, column {}: {}",
+                line, column, message
+            ),
+            TransformError::Schema(violations) => {
+                write!(
+                    f,
+                    "CSV file does not match the schema ({} violation(s))",
+                    violations.len()
+                )?;
+                for violation in violations {
+                    write!(f, "\n  {}", violation)?;
+                }
+                Ok(())
+            }
+            TransformError::OutputTooLarge { limit } => {
+                write!(f, "Output is larger than the maximum of {} bytes", limit)
+            }
+            TransformError::Stage {
+                index,
+                operation,
+                source,
+            } => write!(f, "Stage {} ({}) failed: {}", index, operation, source),
+        }
+    }
+}
+
+impl Error for TransformError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TransformError::Io(err) => Some(err),
+            TransformError::Stage { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TransformError {
+    fn from(err: io::Error) -> Self {
+        TransformError::Io(err)
+    }
+}
+
+impl From<csv::Error> for TransformError {
+    fn from(err: csv::Error) -> Self {
+        let line = err.position().map(|position| position.line());
+        let description = err.to_string();
+        let (field, message) = match err.into_kind() {
+            csv::ErrorKind::Io(err) => return TransformError::Io(err),
+            csv::ErrorKind::Utf8 { err, .. } => {
+                (Some(err.field() as u64 + 1), "invalid UTF-8".to_string())
+            }
+            csv::ErrorKind::UnequalLengths {
+                expected_len, len, ..
+            } => (
+                None,
+                format!(
+                    "found record with {} fields, but the previous record has {} fields",
+                    len, expected_len
+                ),
+            ),
+            _ => (None, description),
+        };
+
+        TransformError::Csv {
+            line,
+            field,
+            message,
+        }
+    }
+}
+
+impl From<serde_json::Error> for TransformError {
+    fn from(err: serde_json::Error) -> Self {
+        // serde_json appends the location to its message; it is kept in
+        // separate fields instead.
+        let (line, column) = (err.line(), err.column());
+        let message = err.to_string();
+        let message = message
+            .strip_suffix(&format!(" at line {} column {}", line, column))
+            .unwrap_or(&message)
+            .to_string();
+
+        TransformError::Json {
+            line,
+            column,
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_reports_kind_of_failing_operation() {
+        let err = TransformError::Stage {
+            index: 2,
+            operation: "from-hex".to_string(),
+            source: Box::new(TransformError::Encoding {
+                offset: 3,
+                message: "Invalid base-16 digit 'z'".to_string(),
+            }),
+        };
+
+        assert_eq!(err.kind(), "encoding");
+        assert_eq!(err.code(), "E006");
+        assert_eq!(err.exit_code(), 7);
+        assert_eq!(
+            err.to_string(),
+            "Stage 2 (from-hex) failed: Invalid base-16 digit 'z' at byte offset 3"
+        );
+    }
+
+    #[test]
+    fn test_csv_errors_keep_their_position() {
+        let data = "a,b\n1,2\n3\n";
+        let err = csv::Reader::from_reader(data.as_bytes())
+            .records()
+            .find_map(Result::err)
+            .unwrap();
+
+        assert_eq!(
+            TransformError::from(err).to_string(),
+            "CSV error on line 3: found record with 1 fields, but the previous record has 2 fields"
+        );
+    }
+
+    #[test]
+    fn test_json_errors_keep_their_position() {
+        let err = serde_json::from_str::<serde_json::Value>("[1,\n 2,,]").unwrap_err();
+
+        assert_eq!(
+            TransformError::from(err).to_string(),
+            "JSON error on line 2, column 4: expected value"
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Text transformations shared by the command-line tools and services.
+//!
+//! Operations are looked up by name in a [`Registry`] and can be chained
+//! into a [`Pipeline`]. [`transform`] is the simplest way in: it parses a
+//! pipeline such as `lowercase | replace("\s+", "-")` and runs it on a
+//! piece of text.
+//!
+//! ```
+//! use transformer::{transform, Options};
+//!
+//! let output = transform("lowercase | no-spaces", "Hello World", &Options::default()).unwrap();
+//! assert_eq!(output.text, "helloworld");
+//! ```
+
+pub mod args;
+pub mod config;
+pub mod error;
+pub mod operation;
+pub mod operations;
+pub mod pipeline;
+pub mod report;
+pub mod source;
+mod table;
+pub mod validation;
+
+pub use config::Config;
+pub use error::TransformError;
+pub use operation::{Operation, Registry};
+pub use pipeline::Pipeline;
+pub use validation::ValidationPolicy;
+
+use std::sync::OnceLock;
+
+/// Settings for [`transform`].
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Aliases, default arguments and the rules the input is checked
+    /// against.
+    pub config: Config,
+    /// Whether the input may name a file (`@path`) or stdin (`-`) instead of
+    /// being the text itself, and operations that open files named in their
+    /// arguments may run. Off by default, so users of a service can't make it
+    /// read local files.
+    pub read_sources: bool,
+}
+
+/// The result of a successful [`transform`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    pub text: String,
+}
+
+/// The registry of every built-in operation, built on first use.
+pub fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(operations::default_registry)
+}
+
+/// Runs `op`, a single operation or a pipeline of them, on `input`.
+/// Aliases and default arguments come from `options.config`.
+///
+/// Pipeline syntax errors, and operations that open files when
+/// `options.read_sources` is off, are reported as
+/// [`TransformError::InvalidArgument`]; a failing stage is reported as
+/// [`TransformError::Stage`].
+pub fn transform(op: &str, input: &str, options: &Options) -> Result<Output, TransformError> {
+    let pipeline = Pipeline::parse(op)
+        .and_then(|pipeline| pipeline.configure(&options.config))
+        .map_err(TransformError::InvalidArgument)?;
+    if !options.read_sources {
+        let opens_files = pipeline.stages().iter().find(|stage| {
+            registry()
+                .get(&stage.name)
+                .is_some_and(|op| op.opens_files())
+        });
+        if let Some(stage) = opens_files {
+            return Err(TransformError::InvalidArgument(format!(
+                "{} opens files and is not available here",
+                stage.name
+            )));
+        }
+    }
+    let policy = &options.config.validation;
+    let text = match options.read_sources {
+        true => pipeline.run(registry(), policy, input)?,
+        false => pipeline.run_text(registry(), policy, input)?,
+    };
+    Ok(Output { text })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, process};
+
+    #[test]
+    fn test_transform_runs_pipelines_with_arguments() {
+        let output = transform(
+            r#"lowercase | replace("\s+", "-")"#,
+            "Hello  Big World",
+            &Options::default(),
+        )
+        .unwrap();
+
+        assert_eq!(output.text, "hello-big-world");
+    }
+
+    #[test]
+    fn test_transform_reports_errors() {
+        let options = Options::default();
+
+        assert_eq!(
+            transform("lowercse", "Hi", &options).unwrap_err().code(),
+            "E002"
+        );
+        assert_eq!(
+            transform("lowercase |", "Hi", &options)
+                .unwrap_err()
+                .to_string(),
+            "Missing operation name in pipeline"
+        );
+        assert_eq!(
+            transform("uppercase", "", &options).unwrap_err().code(),
+            "E001"
+        );
+    }
+
+    #[test]
+    fn test_transform_renders_csv_text() {
+        let options = Options::default();
+
+        assert_eq!(
+            transform("csv(border=ascii)", "a , b\n 1,x ", &options)
+                .unwrap()
+                .text,
+            "+---+---+\n| a | b |\n+---+---+\n| 1 | x |\n+---+---+"
+        );
+    }
+
+    #[test]
+    fn test_transform_applies_config() {
+        let config = Config::parse(
+            r#"
+            [aliases]
+            table = "csv"
+
+            [defaults.csv]
+            delimiter = ";"
+            border = "ascii"
+            "#,
+        )
+        .unwrap();
+        let options = Options {
+            config,
+            ..Options::default()
+        };
+
+        assert_eq!(
+            transform("table", "a;b\n1;2", &options).unwrap().text,
+            "+---+---+\n| a | b |\n+---+---+\n| 1 | 2 |\n+---+---+"
+        );
+    }
+
+    #[test]
+    fn test_delimiter_default_applies_to_every_csv_operation() {
+        let mut config = Config::default();
+        config.insert_default("delimiter", ";");
+        let semicolons = Options {
+            config,
+            ..Options::default()
+        };
+
+        for op in [
+            "csv",
+            "csv-query",
+            "csv-group(\"by a\")",
+            "csv-to-json",
+            "csv-to-markdown",
+            "csv-to-html",
+        ] {
+            assert_eq!(
+                transform(op, "a;b\n1;2", &semicolons).unwrap(),
+                transform(op, "a,b\n1,2", &Options::default()).unwrap(),
+                "{}",
+                op
+            );
+        }
+    }
+
+    #[test]
+    fn test_transform_never_reads_files_without_read_sources() {
+        let path = env::temp_dir().join(format!("transformer-{}.csv", process::id()));
+        let path = path.to_str().unwrap();
+        let options = Options::default();
+
+        let mut runs = vec![format!("csv-validate({})", path)];
+        for operation in registry().operations() {
+            runs.push(operation.name().to_string());
+        }
+        let outputs = || {
+            let mut outputs = Vec::new();
+            for op in &runs {
+                for input in [path.to_string(), format!("@{}", path)] {
+                    let result = transform(op, &input, &options);
+                    outputs.push(format!("{} {}: {:?}", op, input, result));
+                }
+            }
+            outputs
+        };
+
+        // The results must not depend on whether the file exists.
+        fs::write(path, "name,secret\nJo,42\n").unwrap();
+        let with_file = outputs();
+        fs::remove_file(path).unwrap();
+        assert_eq!(with_file, outputs());
+        assert!(with_file[0].contains("csv-validate opens files"));
+    }
+}
@@ -0,0 +1,188 @@
+use crate::args::Args;
+use crate::error::TransformError;
+use crate::source::Source;
+use std::collections::BTreeMap;
+
+/// A single text transformation that can be looked up by name.
+pub trait Operation: Send + Sync {
+    /// Name used to invoke the operation, e.g. `lowercase`.
+    fn name(&self) -> &'static str;
+
+    /// One-line summary shown by `help`.
+    fn description(&self) -> &'static str;
+
+    /// Usage line shown by `help <operation>`.
+    fn usage(&self) -> &'static str;
+
+    fn run(&self, input: &str) -> Result<String, TransformError>;
+
+    /// Named arguments the operation accepts, e.g. `width` for `wrap`.
+    fn named_args(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Runs the operation with the arguments given in parentheses after its
+    /// name. Most operations take none, so any arguments are rejected unless
+    /// this is overridden.
+    fn run_with(&self, input: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 0, &[])?;
+        self.run(input)
+    }
+
+    /// Whether the operation opens files named in its arguments, like the
+    /// schema of `csv-validate`.
+    fn opens_files(&self) -> bool {
+        false
+    }
+
+    /// An upper bound on the length in bytes of the output for `input`, for
+    /// operations whose output can be far larger than their input, like
+    /// `replace` or a padded table. Pipelines with a
+    /// [`ValidationPolicy::max_output`](crate::ValidationPolicy::max_output)
+    /// check it before running the operation, so the output is never built.
+    /// `None` when the output is at most a few times the input, or when the
+    /// arguments are invalid and running the operation will say so.
+    fn output_bound(&self, _input: &str, _args: &Args) -> Option<usize> {
+        None
+    }
+
+    /// Runs the operation on input that still has to be read from a file or
+    /// stdin. Operations that work on raw bytes override this so the input
+    /// is not decoded as text first.
+    fn run_source(&self, source: Source, args: &Args) -> Result<String, TransformError> {
+        self.run_with(&source.read()?, args)
+    }
+}
+
+#[derive(Default)]
+pub struct Registry {
+    operations: BTreeMap<&'static str, Box<dyn Operation>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, operation: Box<dyn Operation>) {
+        self.operations.insert(operation.name(), operation);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Operation> {
+        self.operations.get(name).map(|op| op.as_ref())
+    }
+
+    /// Every registered operation, sorted by name.
+    pub fn operations(&self) -> impl Iterator<Item = &dyn Operation> {
+        self.operations.values().map(|op| op.as_ref())
+    }
+
+    /// Looks up an operation, returning an error with a "did you mean" hint
+    /// when the name is unknown.
+    pub fn find(&self, name: &str) -> Result<&dyn Operation, TransformError> {
+        self.get(name)
+            .ok_or_else(|| TransformError::UnknownOperation {
+                name: name.to_string(),
+                suggestion: self.suggest(name).map(String::from),
+            })
+    }
+
+    /// Returns the registered name closest to `name`, if any is close enough
+    /// to be a plausible typo.
+    pub fn suggest(&self, name: &str) -> Option<&'static str> {
+        self.operations
+            .keys()
+            .map(|candidate| (*candidate, strsim::levenshtein(name, candidate)))
+            .filter(|(candidate, distance)| *distance <= 2.max(candidate.len() / 3))
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(candidate, _)| candidate)
+    }
+
+    /// Lists all operations with their descriptions.
+    pub fn help(&self) -> String {
+        let width = self
+            .operations
+            .keys()
+            .map(|name| name.len())
+            .max()
+            .unwrap_or(0);
+        let mut lines = vec!["Available operations:".to_string()];
+        lines.extend(
+            self.operations
+                .values()
+                .map(|op| format!("  {:width$}  {}", op.name(), op.description())),
+        );
+        lines.extend(
+            [
+                "",
+                "Input:",
+                "  <text>  Literal text",
+                "  @path   The contents of the file at path",
+                "  -       Standard input",
+                "  '...'   Literal text that may start with @ or - ('' for a quote)",
+            ]
+            .map(String::from),
+        );
+        lines.push("Type 'help <operation>' for usage.".to_string());
+        lines.join("\n")
+    }
+
+    /// Describes a single operation, including its usage line.
+    pub fn help_for(&self, name: &str) -> Result<String, TransformError> {
+        let op = self.find(name)?;
+        Ok(format!(
+            "{} - {}\nUsage: {}",
+            op.name(),
+            op.description(),
+            op.usage()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo;
+
+    impl Operation for Echo {
+        fn name(&self) -> &'static str {
+            "echo"
+        }
+
+        fn description(&self) -> &'static str {
+            "Returns the input unchanged"
+        }
+
+        fn usage(&self) -> &'static str {
+            "echo <text>"
+        }
+
+        fn run(&self, input: &str) -> Result<String, TransformError> {
+            Ok(input.to_string())
+        }
+    }
+
+    #[test]
+    fn test_find_registered_operation() {
+        let mut registry = Registry::new();
+        registry.register(Box::new(Echo));
+
+        assert_eq!(registry.find("echo").unwrap().run("hi").unwrap(), "hi");
+    }
+
+    #[test]
+    fn test_unknown_operation_suggests_closest_name() {
+        let mut registry = Registry::new();
+        registry.register(Box::new(Echo));
+
+        assert_eq!(registry.suggest("ecoh"), Some("echo"));
+        assert_eq!(registry.suggest("uppercase"), None);
+
+        let err = registry.find("ecoh").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Invalid operation: ecoh (did you mean 'echo'?)"
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use crate::error::TransformError;
+use crate::operation::Operation;
+
+pub struct Lowercase;
+
+impl Operation for Lowercase {
+    fn name(&self) -> &'static str {
+        "lowercase"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts the text to lowercase"
+    }
+
+    fn usage(&self) -> &'static str {
+        "lowercase <text | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        Ok(input.to_lowercase())
+    }
+}
+
+pub struct Uppercase;
+
+impl Operation for Uppercase {
+    fn name(&self) -> &'static str {
+        "uppercase"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts the text to uppercase"
+    }
+
+    fn usage(&self) -> &'static str {
+        "uppercase <text | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        Ok(input.to_uppercase())
+    }
+}
+
+/// Converts identifiers and phrases between naming conventions. The
+/// identifier conventions split the text into words first; title and
+/// sentence case only change letters and keep everything else.
+pub struct CaseConversion {
+    name: &'static str,
+    description: &'static str,
+    usage: &'static str,
+    convert: fn(&str) -> String,
+}
+
+impl Operation for CaseConversion {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn usage(&self) -> &'static str {
+        self.usage
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        Ok((self.convert)(input))
+    }
+}
+
+pub fn conversions() -> Vec<Box<dyn Operation>> {
+    vec![
+        Box::new(CaseConversion {
+            name: "camel-case",
+            description: "Converts the text to camelCase",
+            usage: "camel-case <text | @path>",
+            convert: |input| {
+                split_words(input)
+                    .iter()
+                    .enumerate()
+                    .map(|(i, word)| match i {
+                        0 => word.to_lowercase(),
+                        _ => capitalize(word),
+                    })
+                    .collect()
+            },
+        }),
+        Box::new(CaseConversion {
+            name: "pascal-case",
+            description: "Converts the text to PascalCase",
+            usage: "pascal-case <text | @path>",
+            convert: |input| {
+                split_words(input)
+                    .iter()
+                    .map(|word| capitalize(word))
+                    .collect()
+            },
+        }),
+        Box::new(CaseConversion {
+            name: "snake-case",
+            description: "Converts the text to snake_case",
+            usage: "snake-case <text | @path>",
+            convert: |input| join_lowercase(&split_words(input), "_"),
+        }),
+        Box::new(CaseConversion {
+            name: "screaming-snake",
+            description: "Converts the text to SCREAMING_SNAKE_CASE",
+            usage: "screaming-snake <text | @path>",
+            convert: |input| join_lowercase(&split_words(input), "_").to_uppercase(),
+        }),
+        Box::new(CaseConversion {
+            name: "kebab-case",
+            description: "Converts the text to kebab-case",
+            usage: "kebab-case <text | @path>",
+            convert: |input| join_lowercase(&split_words(input), "-"),
+        }),
+        Box::new(CaseConversion {
+            name: "title-case",
+            description: "Converts the text to Title Case",
+            usage: "title-case <text | @path>",
+            convert: title_case,
+        }),
+        Box::new(CaseConversion {
+            name: "sentence-case",
+            description: "Converts the text to Sentence case",
+            usage: "sentence-case <text | @path>",
+            convert: sentence_case,
+        }),
+    ]
+}
+
+fn join_lowercase(words: &[String], separator: &str) -> String {
+    words
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<String>>()
+        .join(separator)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Capitalizes the first letter or digit of every lowercase
+/// whitespace-separated word, so `don't stop` becomes `Don't Stop`. Words
+/// with any uppercase letter, like `NASA` or `iPhone`, are kept as written.
+fn title_case(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for word in input.split_inclusive(char::is_whitespace) {
+        let first = word.char_indices().find(|(_, c)| c.is_alphanumeric());
+        match first {
+            Some((index, c)) if !word.chars().any(char::is_uppercase) => {
+                output.push_str(&word[..index]);
+                output.extend(c.to_uppercase());
+                output.push_str(&word[index + c.len_utf8()..]);
+            }
+            _ => output.push_str(word),
+        }
+    }
+
+    output
+}
+
+/// Lowercases the text and capitalizes the first letter or digit of every
+/// sentence, i.e. at the start and after `.`, `!` or `?` and whitespace.
+fn sentence_case(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut sentence_start = true;
+    let mut after_end = false;
+
+    for c in input.chars() {
+        if c.is_alphanumeric() {
+            match sentence_start {
+                true => output.extend(c.to_uppercase()),
+                false => output.extend(c.to_lowercase()),
+            }
+            sentence_start = false;
+            after_end = false;
+        } else {
+            after_end |= matches!(c, '.' | '!' | '?');
+            sentence_start |= after_end && c.is_whitespace();
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+/// Splits text into words on separators (anything that is not a letter or
+/// digit) and on case changes inside identifiers.
+///
+/// `parseHTTPResponse2XX` becomes `parse`, `HTTP`, `Response2`, `XX`: a new
+/// word starts at a lower-to-upper change, at the last capital of an acronym
+/// followed by lowercase, and at a capital following a digit. Digits stay
+/// attached to the word before them.
+pub fn split_words(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+
+    for part in input.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<char> = part.chars().collect();
+        let mut word = String::new();
+
+        for (i, &c) in chars.iter().enumerate() {
+            if i > 0 && c.is_uppercase() {
+                let previous = chars[i - 1];
+                let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+                let boundary = previous.is_lowercase()
+                    || previous.is_numeric()
+                    || (previous.is_uppercase() && next_is_lower);
+                if boundary && !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            word.push(c);
+        }
+
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(name: &str, input: &str) -> String {
+        conversions()
+            .into_iter()
+            .find(|operation| operation.name() == name)
+            .unwrap()
+            .run(input)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_split_words_detects_boundaries() {
+        assert_eq!(
+            split_words("parseHTTPResponse2XX"),
+            ["parse", "HTTP", "Response2", "XX"]
+        );
+        assert_eq!(split_words("HTML5Parser"), ["HTML5", "Parser"]);
+        assert_eq!(
+            split_words("already_snake-and kebab"),
+            ["already", "snake", "and", "kebab"]
+        );
+        assert_eq!(split_words("  --  "), Vec::<String>::new());
+        assert_eq!(split_words("žluťoučkýKůň"), ["žluťoučký", "Kůň"]);
+    }
+
+    #[test]
+    fn test_case_conversions() {
+        let input = "XMLHttpRequest for user_id 42";
+
+        assert_eq!(convert("camel-case", input), "xmlHttpRequestForUserId42");
+        assert_eq!(convert("pascal-case", input), "XmlHttpRequestForUserId42");
+        assert_eq!(
+            convert("snake-case", input),
+            "xml_http_request_for_user_id_42"
+        );
+        assert_eq!(
+            convert("screaming-snake", input),
+            "XML_HTTP_REQUEST_FOR_USER_ID_42"
+        );
+        assert_eq!(
+            convert("kebab-case", input),
+            "xml-http-request-for-user-id-42"
+        );
+    }
+
+    #[test]
+    fn test_prose_conversions_keep_punctuation() {
+        assert_eq!(convert("title-case", "don't stop"), "Don't Stop");
+        assert_eq!(
+            convert("title-case", "the (QUICK) fox, 2nd  try"),
+            "The (QUICK) Fox, 2nd  Try"
+        );
+        assert_eq!(
+            convert("title-case", "the NASA report on my iPhone"),
+            "The NASA Report On My iPhone"
+        );
+        assert_eq!(
+            convert("sentence-case", "hello, world. bye!"),
+            "Hello, world. Bye!"
+        );
+        assert_eq!(
+            convert("sentence-case", "WHAT?! it's 3.5 KM. \"ok.\" fine"),
+            "What?! It's 3.5 km. \"Ok.\" Fine"
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use super::query::{column_index, Parser};
+use super::{delimiter, read_records};
+use crate::args::Args;
+use crate::error::TransformError;
+use crate::operation::Operation;
+use crate::table::{is_numeric_column, Table, TableStyle};
+use csv::StringRecord;
+use std::collections::BTreeMap;
+
+pub struct CsvGroup;
+
+impl Operation for CsvGroup {
+    fn name(&self) -> &'static str {
+        "csv-group"
+    }
+
+    fn description(&self) -> &'static str {
+        "Groups CSV rows by a column and summarizes numeric columns"
+    }
+
+    fn usage(&self) -> &'static str {
+        "csv-group(\"by <column> [of <numeric column>, ...]\"[, delimiter=<char>, border=unicode|ascii, bold=true, max-width=<n>]) <csv | @path>"
+    }
+
+    fn run(&self, content: &str) -> Result<String, TransformError> {
+        self.run_with(content, &Args::default())
+    }
+
+    fn named_args(&self) -> &'static [&'static str] {
+        &["delimiter", "border", "bold", "max-width"]
+    }
+
+    fn run_with(&self, content: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 1, self.named_args())?;
+        let style = TableStyle::from_args(args)?;
+        Ok(table(content, args)?.render(&style))
+    }
+
+    fn output_bound(&self, content: &str, args: &Args) -> Option<usize> {
+        let style = TableStyle::from_args(args).ok()?;
+        Some(table(content, args).ok()?.rendered_len(&style))
+    }
+}
+
+fn table(content: &str, args: &Args) -> Result<Table, TransformError> {
+    let grouping =
+        Grouping::parse(args.required(0, "grouping")?).map_err(TransformError::InvalidArgument)?;
+    let (headers, records) = read_records(content, delimiter(args)?)?;
+
+    let (headers, rows) = grouping
+        .execute(&headers, &records)
+        .map_err(TransformError::InvalidArgument)?;
+    let mut table = Table::new(headers);
+    for row in rows {
+        table.push_row(row);
+    }
+    Ok(table)
+}
+
+/// A parsed `csv-group` command.
+#[derive(Debug, PartialEq)]
+pub struct Grouping {
+    by: String,
+    of: Vec<String>,
+}
+
+#[derive(Default)]
+struct Summary {
+    sum: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+    values: usize,
+}
+
+impl Summary {
+    fn add(&mut self, value: f64) {
+        self.sum += value;
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+        self.values += 1;
+    }
+
+    fn average(&self) -> Option<f64> {
+        (self.values > 0).then(|| self.sum / self.values as f64)
+    }
+}
+
+impl Grouping {
+    pub fn parse(input: &str) -> Result<Grouping, String> {
+        let mut parser = Parser::new(input)?;
+        if !parser.next_if_keyword("by") {
+            return Err("Expected 'by <column>'".to_string());
+        }
+        let by = parser.expect_value("a column to group by")?;
+
+        let mut of = Vec::new();
+        if parser.next_if_keyword("of") {
+            loop {
+                of.push(parser.expect_value("a column name")?);
+                if !parser.next_if_comma() {
+                    break;
+                }
+            }
+        }
+
+        if let Some(token) = parser.next() {
+            return Err(format!("Unexpected token in grouping: {:?}", token));
+        }
+
+        Ok(Grouping { by, of })
+    }
+
+    /// Groups `records` and returns the summary table's headers and rows.
+    ///
+    /// Without an explicit `of` list every numeric column is summarized.
+    pub fn execute(
+        &self,
+        headers: &StringRecord,
+        records: &[StringRecord],
+    ) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+        let group_index = column_index(headers, &self.by)?;
+        let columns: Vec<usize> = if self.of.is_empty() {
+            (0..headers.len())
+                .filter(|i| *i != group_index)
+                .filter(|i| is_numeric_column(records.iter().map(|r| r.get(*i).unwrap_or(""))))
+                .collect()
+        } else {
+            self.of
+                .iter()
+                .map(|column| column_index(headers, column))
+                .collect::<Result<_, _>>()?
+        };
+
+        let mut groups: BTreeMap<&str, (usize, Vec<Summary>)> = BTreeMap::new();
+        for (row, record) in records.iter().enumerate() {
+            let key = record.get(group_index).unwrap_or("");
+            let (count, summaries) = groups
+                .entry(key)
+                .or_insert_with(|| (0, columns.iter().map(|_| Summary::default()).collect()));
+            *count += 1;
+
+            for (summary, &column) in summaries.iter_mut().zip(&columns) {
+                let cell = record.get(column).unwrap_or("").trim();
+                if cell.is_empty() {
+                    continue;
+                }
+                let value = cell.parse::<f64>().map_err(|_| {
+                    format!(
+                        "Column '{}' is not numeric: found '{}' on line {}",
+                        &headers[column],
+                        cell,
+                        row + 2
+                    )
+                })?;
+                summary.add(value);
+            }
+        }
+
+        let mut output_headers = vec![headers[group_index].to_string(), "count".to_string()];
+        for &column in &columns {
+            for aggregate in ["sum", "min", "max", "avg"] {
+                output_headers.push(format!("{}({})", aggregate, &headers[column]));
+            }
+        }
+
+        let rows = groups
+            .into_iter()
+            .map(|(key, (count, summaries))| {
+                let mut row = vec![key.to_string(), count.to_string()];
+                for summary in &summaries {
+                    row.push(format_number(Some(summary.sum)));
+                    row.push(format_number(summary.min));
+                    row.push(format_number(summary.max));
+                    row.push(format_number(summary.average()));
+                }
+                row
+            })
+            .collect();
+
+        Ok((output_headers, rows))
+    }
+}
+
+/// Prints whole numbers without a fraction and everything else with at most
+/// two decimal places.
+fn format_number(value: Option<f64>) -> String {
+    match value {
+        Some(value) if value.fract() == 0.0 => format!("{}", value),
+        Some(value) => format!("{:.2}", value)
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sales() -> (StringRecord, Vec<StringRecord>) {
+        let headers = StringRecord::from(vec!["Region", "Units", "Price", "Rep"]);
+        let records = vec![
+            StringRecord::from(vec!["North", "10", "2.5", "Ann"]),
+            StringRecord::from(vec!["South", "4", "3", "Bob"]),
+            StringRecord::from(vec!["North", "5", "", "Cid"]),
+        ];
+        (headers, records)
+    }
+
+    #[test]
+    fn test_group_summarizes_numeric_columns() {
+        let (headers, records) = sales();
+        let grouping = Grouping::parse("by Region").unwrap();
+        let (headers, rows) = grouping.execute(&headers, &records).unwrap();
+
+        assert_eq!(
+            headers,
+            [
+                "Region",
+                "count",
+                "sum(Units)",
+                "min(Units)",
+                "max(Units)",
+                "avg(Units)",
+                "sum(Price)",
+                "min(Price)",
+                "max(Price)",
+                "avg(Price)"
+            ]
+        );
+        assert_eq!(
+            rows,
+            [
+                ["North", "2", "15", "5", "10", "7.5", "2.5", "2.5", "2.5", "2.5"],
+                ["South", "1", "4", "4", "4", "4", "3", "3", "3", "3"]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_rejects_non_numeric_column() {
+        let (headers, records) = sales();
+        let grouping = Grouping::parse("by Region of Units, Rep").unwrap();
+
+        assert_eq!(
+            grouping.execute(&headers, &records).unwrap_err(),
+            "Column 'Rep' is not numeric: found 'Ann' on line 2"
+        );
+    }
+
+    #[test]
+    fn test_parse_requires_group_column() {
+        assert!(Grouping::parse("").is_err());
+        assert!(Grouping::parse("Region").is_err());
+        assert!(Grouping::parse("by Region extra").is_err());
+    }
+}
@@ -0,0 +1,487 @@
+use super::{delimiter, read_records};
+use crate::args::Args;
+use crate::error::TransformError;
+use crate::operation::Operation;
+use crate::table::is_numeric_column;
+use csv::StringRecord;
+use serde_json::{Map, Number, Value};
+use std::collections::HashSet;
+use unicode_width::UnicodeWidthStr;
+
+pub struct CsvToJson;
+
+impl Operation for CsvToJson {
+    fn name(&self) -> &'static str {
+        "csv-to-json"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts CSV data into a JSON array of objects keyed by header"
+    }
+
+    fn usage(&self) -> &'static str {
+        "csv-to-json([delimiter=<char>]) <csv | @path>"
+    }
+
+    fn named_args(&self) -> &'static [&'static str] {
+        &["delimiter"]
+    }
+
+    fn run(&self, content: &str) -> Result<String, TransformError> {
+        self.run_with(content, &Args::default())
+    }
+
+    fn run_with(&self, content: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 0, self.named_args())?;
+        let (headers, records) = read_records(content, delimiter(args)?)?;
+        Ok(serde_json::to_string_pretty(&to_json(&headers, &records))?)
+    }
+
+    /// Every record repeats the headers as keys. A value is never longer
+    /// than its cell as a JSON string, or `null`.
+    fn output_bound(&self, content: &str, args: &Args) -> Option<usize> {
+        let (headers, records) = read_records(content, delimiter(args).ok()?).ok()?;
+        // Indentation, `: ` and `,` plus a line break for every field.
+        let keys: usize = headers
+            .iter()
+            .map(|header| json_string_len(header) + 8)
+            .sum();
+        let values: usize = records
+            .iter()
+            .flat_map(|record| (0..headers.len()).map(|i| record.get(i).unwrap_or("")))
+            .map(|cell| json_string_len(cell).max("null".len()))
+            .sum();
+        Some(records.len() * (keys + 8) + values + 4)
+    }
+}
+
+pub struct JsonToCsv;
+
+impl Operation for JsonToCsv {
+    fn name(&self) -> &'static str {
+        "json-to-csv"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts a JSON array of objects into CSV"
+    }
+
+    fn usage(&self) -> &'static str {
+        "json-to-csv <json | @path>"
+    }
+
+    fn run(&self, content: &str) -> Result<String, TransformError> {
+        let value: Value = serde_json::from_str(content)?;
+        from_json(&value)
+    }
+
+    /// Every row gets a field for every key of any row, and quoting makes a
+    /// field at most twice as long.
+    fn output_bound(&self, content: &str, _args: &Args) -> Option<usize> {
+        let value: Value = serde_json::from_str(content).ok()?;
+        let rows = value.as_array().map_or(1, Vec::len);
+        let keys = match &value {
+            Value::Array(rows) => rows
+                .iter()
+                .filter_map(Value::as_object)
+                .flat_map(Map::keys)
+                .collect::<HashSet<_>>()
+                .len(),
+            Value::Object(object) => object.len(),
+            _ => 0,
+        };
+        Some(2 * content.len() + (rows + 1) * (3 * keys + 1))
+    }
+}
+
+pub struct CsvToMarkdown;
+
+impl Operation for CsvToMarkdown {
+    fn name(&self) -> &'static str {
+        "csv-to-markdown"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts CSV data into a Markdown table"
+    }
+
+    fn usage(&self) -> &'static str {
+        "csv-to-markdown([delimiter=<char>]) <csv | @path>"
+    }
+
+    fn named_args(&self) -> &'static [&'static str] {
+        &["delimiter"]
+    }
+
+    fn run(&self, content: &str) -> Result<String, TransformError> {
+        self.run_with(content, &Args::default())
+    }
+
+    fn run_with(&self, content: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 0, self.named_args())?;
+        let (headers, records) = read_records(content, delimiter(args)?)?;
+        Ok(to_markdown(&headers, &records))
+    }
+
+    /// Every line pads each cell to the width of its column.
+    fn output_bound(&self, content: &str, args: &Args) -> Option<usize> {
+        let (headers, records) = read_records(content, delimiter(args).ok()?).ok()?;
+        let mut widths = 0;
+        let mut text = 0;
+        for i in 0..headers.len() {
+            let mut width = 3;
+            for cell in records
+                .iter()
+                .map(|record| record.get(i).unwrap_or(""))
+                .chain([&headers[i]])
+            {
+                let cell = escape_markdown(cell);
+                width = width.max(cell.width());
+                text += cell.len();
+            }
+            widths += width;
+        }
+        Some((records.len() + 2) * (widths + 3 * headers.len() + 2) + text)
+    }
+}
+
+pub struct CsvToHtml;
+
+impl Operation for CsvToHtml {
+    fn name(&self) -> &'static str {
+        "csv-to-html"
+    }
+
+    fn description(&self) -> &'static str {
+        "Converts CSV data into an HTML table"
+    }
+
+    fn usage(&self) -> &'static str {
+        "csv-to-html([delimiter=<char>]) <csv | @path>"
+    }
+
+    fn named_args(&self) -> &'static [&'static str] {
+        &["delimiter"]
+    }
+
+    fn run(&self, content: &str) -> Result<String, TransformError> {
+        self.run_with(content, &Args::default())
+    }
+
+    fn run_with(&self, content: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 0, self.named_args())?;
+        let (headers, records) = read_records(content, delimiter(args)?)?;
+        Ok(to_html(&headers, &records))
+    }
+}
+
+fn numeric_columns(headers: &StringRecord, records: &[StringRecord]) -> Vec<bool> {
+    (0..headers.len())
+        .map(|i| is_numeric_column(records.iter().map(|record| record.get(i).unwrap_or(""))))
+        .collect()
+}
+
+/// Builds one JSON object per record. A column becomes JSON numbers (or
+/// `null` when empty) only if every value in it is a JSON number written
+/// exactly as JSON would write it back, so zip codes like `01234`, `+5`,
+/// `nan` or `inf` keep the whole column as strings. Strings are kept as
+/// written, surrounding whitespace included.
+pub fn to_json(headers: &StringRecord, records: &[StringRecord]) -> Value {
+    let numeric: Vec<bool> = (0..headers.len())
+        .map(|i| {
+            let mut cells = records.iter().map(|record| record.get(i).unwrap_or(""));
+            let mut seen = false;
+            let all_numbers = cells.all(|cell| {
+                seen |= !cell.trim().is_empty();
+                cell.trim().is_empty() || json_number(cell).is_some()
+            });
+            seen && all_numbers
+        })
+        .collect();
+
+    let rows = records
+        .iter()
+        .map(|record| {
+            let mut object = Map::new();
+            for (i, header) in headers.iter().enumerate() {
+                let cell = record.get(i).unwrap_or("");
+                let value = match numeric[i] {
+                    true => json_number(cell).map_or(Value::Null, Value::Number),
+                    false => Value::String(cell.to_string()),
+                };
+                object.insert(header.to_string(), value);
+            }
+            Value::Object(object)
+        })
+        .collect();
+
+    Value::Array(rows)
+}
+
+/// Parses `cell`, ignoring surrounding whitespace, as a JSON number that
+/// prints back exactly as written.
+fn json_number(cell: &str) -> Option<Number> {
+    let cell = cell.trim();
+    cell.parse::<Number>()
+        .ok()
+        .filter(|number| number.to_string() == cell)
+}
+
+/// The length of `text` written as a JSON string, quotes included.
+fn json_string_len(text: &str) -> usize {
+    let escaped: usize = text
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' | '\n' | '\r' | '\t' | '\u{8}' | '\u{c}' => 2,
+            c if c < ' ' => 6,
+            c => c.len_utf8(),
+        })
+        .sum();
+    escaped + 2
+}
+
+/// Turns an array of objects (or a single object) into CSV. The header is
+/// the union of all keys in the order they first appear; nested values are
+/// written as compact JSON.
+pub fn from_json(value: &Value) -> Result<String, TransformError> {
+    let rows = match value {
+        Value::Array(rows) => rows.as_slice(),
+        Value::Object(_) => std::slice::from_ref(value),
+        _ => {
+            return Err(TransformError::InvalidArgument(
+                "Expected a JSON array of objects".to_string(),
+            ))
+        }
+    };
+
+    let mut headers: Vec<&str> = Vec::new();
+    for (index, row) in rows.iter().enumerate() {
+        let object = row.as_object().ok_or_else(|| {
+            TransformError::InvalidArgument(format!(
+                "Expected a JSON object at index {}, found {}",
+                index, row
+            ))
+        })?;
+        for key in object.keys() {
+            if !headers.contains(&key.as_str()) {
+                headers.push(key);
+            }
+        }
+    }
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&headers)?;
+    for row in rows {
+        writer.write_record(headers.iter().map(|header| match row.get(*header) {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(text)) => text.clone(),
+            Some(other) => other.to_string(),
+        }))?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    // Every field came from a `&str`, so the output is always valid UTF-8.
+    let csv = String::from_utf8_lossy(&bytes);
+    Ok(csv.strip_suffix('\n').unwrap_or(&csv).to_string())
+}
+
+pub fn to_markdown(headers: &StringRecord, records: &[StringRecord]) -> String {
+    let numeric = numeric_columns(headers, records);
+
+    let headers: Vec<String> = headers.iter().map(escape_markdown).collect();
+    let rows: Vec<Vec<String>> = records
+        .iter()
+        .map(|record| {
+            (0..headers.len())
+                .map(|i| escape_markdown(record.get(i).unwrap_or("")))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].width())
+                .chain([headers[i].width(), 3])
+                .max()
+                .unwrap_or(3)
+        })
+        .collect();
+
+    let line = |cells: &[String]| {
+        let cells: Vec<String> = cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                let padding = " ".repeat(widths[i] - cell.width());
+                if numeric[i] {
+                    format!("{}{}", padding, cell)
+                } else {
+                    format!("{}{}", cell, padding)
+                }
+            })
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let separator: Vec<String> = widths
+        .iter()
+        .zip(&numeric)
+        .map(|(width, numeric)| match numeric {
+            true => format!("{}:", "-".repeat(width - 1)),
+            false => "-".repeat(*width),
+        })
+        .collect();
+
+    let mut lines = vec![line(&headers), format!("| {} |", separator.join(" | "))];
+    lines.extend(rows.iter().map(|row| line(row)));
+    lines.join("\n")
+}
+
+/// Keeps a cell inside its Markdown table cell.
+fn escape_markdown(cell: &str) -> String {
+    cell.replace('|', "\\|").replace('\n', "<br>")
+}
+
+pub fn to_html(headers: &StringRecord, records: &[StringRecord]) -> String {
+    let cells = |record: &StringRecord, tag: &str| {
+        (0..headers.len())
+            .map(|i| {
+                let cell = escape_html(record.get(i).unwrap_or(""));
+                format!("<{tag}>{cell}</{tag}>")
+            })
+            .collect::<String>()
+    };
+
+    let mut lines = vec![
+        "<table>".to_string(),
+        "  <thead>".to_string(),
+        format!("    <tr>{}</tr>", cells(headers, "th")),
+        "  </thead>".to_string(),
+        "  <tbody>".to_string(),
+    ];
+    lines.extend(
+        records
+            .iter()
+            .map(|record| format!("    <tr>{}</tr>", cells(record, "td"))),
+    );
+    lines.push("  </tbody>".to_string());
+    lines.push("</table>".to_string());
+    lines.join("\n")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn people() -> (StringRecord, Vec<StringRecord>) {
+        let headers = StringRecord::from(vec!["Name", "Age"]);
+        let records = vec![
+            StringRecord::from(vec!["Jo <3", "32"]),
+            StringRecord::from(vec!["A|B", ""]),
+        ];
+        (headers, records)
+    }
+
+    #[test]
+    fn test_csv_to_json_infers_numbers() {
+        let (headers, records) = people();
+
+        assert_eq!(
+            to_json(&headers, &records).to_string(),
+            r#"[{"Name":"Jo <3","Age":32},{"Name":"A|B","Age":null}]"#
+        );
+    }
+
+    #[test]
+    fn test_csv_to_json_keeps_leading_zeros() {
+        let headers = StringRecord::from(vec!["zip", "id", "price"]);
+        let records = vec![
+            StringRecord::from(vec!["01234", "007", "0.5"]),
+            StringRecord::from(vec!["12345", "8", "-0"]),
+        ];
+
+        assert_eq!(
+            to_json(&headers, &records).to_string(),
+            r#"[{"zip":"01234","id":"007","price":0.5},{"zip":"12345","id":"8","price":-0}]"#
+        );
+    }
+
+    #[test]
+    fn test_csv_to_json_keeps_numbers_and_strings_as_written() {
+        let headers = StringRecord::from(vec!["id", "price", "name", "ratio", "sign"]);
+        let records = vec![
+            StringRecord::from(vec![
+                " 123456789012345678901234567890",
+                "1.50",
+                " Jo ",
+                "nan",
+                "+5",
+            ]),
+            StringRecord::from(vec!["1", "", "Al", "inf", "6"]),
+        ];
+
+        assert_eq!(
+            to_json(&headers, &records).to_string(),
+            concat!(
+                r#"[{"id":123456789012345678901234567890,"price":1.50,"name":" Jo ","ratio":"nan","sign":"+5"},"#,
+                r#"{"id":1,"price":null,"name":"Al","ratio":"inf","sign":"6"}]"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_json_to_csv_keeps_trailing_whitespace() {
+        let value = serde_json::json!([{"a": "x ", "b": "y  "}]);
+
+        assert_eq!(from_json(&value).unwrap(), "a,b\nx ,y  ");
+    }
+
+    #[test]
+    fn test_json_to_csv_round_trip() {
+        let (headers, records) = people();
+        let csv = from_json(&to_json(&headers, &records)).unwrap();
+
+        assert_eq!(csv, "Name,Age\nJo <3,32\nA|B,");
+    }
+
+    #[test]
+    fn test_json_to_csv_merges_keys_and_rejects_non_objects() {
+        let value = serde_json::json!([{"a": 1}, {"b": {"c": true}, "a": "x,y"}]);
+        assert_eq!(
+            from_json(&value).unwrap(),
+            "a,b\n1,\n\"x,y\",\"{\"\"c\"\":true}\""
+        );
+
+        let err = from_json(&serde_json::json!([{"a": 1}, 2])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Expected a JSON object at index 1, found 2"
+        );
+    }
+
+    #[test]
+    fn test_csv_to_markdown() {
+        let (headers, records) = people();
+
+        assert_eq!(
+            to_markdown(&headers, &records),
+            "| Name  | Age |\n| ----- | --: |\n| Jo <3 |  32 |\n| A\\|B  |     |"
+        );
+    }
+
+    #[test]
+    fn test_csv_to_html_escapes_cells() {
+        let (headers, records) = people();
+        let html = to_html(&headers, &records);
+
+        assert!(html.contains("<tr><th>Name</th><th>Age</th></tr>"));
+        assert!(html.contains("<tr><td>Jo &lt;3</td><td>32</td></tr>"));
+    }
+}
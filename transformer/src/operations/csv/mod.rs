@@ -0,0 +1,86 @@
+use crate::args::Args;
+use crate::error::TransformError;
+use crate::operation::Operation;
+use crate::table::{Table, TableStyle};
+use csv::StringRecord;
+
+pub mod aggregate;
+pub mod convert;
+pub mod query;
+pub mod validate;
+
+/// Parses the headers and every record of CSV text whose fields are
+/// separated by `delimiter`.
+pub fn read_records(
+    content: &str,
+    delimiter: u8,
+) -> Result<(StringRecord, Vec<StringRecord>), TransformError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(content.as_bytes());
+    let headers = reader.headers()?.clone();
+    let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+    Ok((headers, records))
+}
+
+pub struct Csv;
+
+impl Operation for Csv {
+    fn name(&self) -> &'static str {
+        "csv"
+    }
+
+    fn description(&self) -> &'static str {
+        "Prints CSV data as a table"
+    }
+
+    fn usage(&self) -> &'static str {
+        "csv([delimiter=<char>, border=unicode|ascii, bold=true, max-width=<n>]) <csv | @path>"
+    }
+
+    fn run(&self, content: &str) -> Result<String, TransformError> {
+        self.run_with(content, &Args::default())
+    }
+
+    fn named_args(&self) -> &'static [&'static str] {
+        &["delimiter", "border", "bold", "max-width"]
+    }
+
+    fn run_with(&self, content: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 0, self.named_args())?;
+        let style = TableStyle::from_args(args)?;
+        Ok(table(content, args)?.render(&style))
+    }
+
+    fn output_bound(&self, content: &str, args: &Args) -> Option<usize> {
+        let style = TableStyle::from_args(args).ok()?;
+        Some(table(content, args).ok()?.rendered_len(&style))
+    }
+}
+
+fn table(content: &str, args: &Args) -> Result<Table, TransformError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter(args)?)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let mut table = Table::new(reader.headers()?);
+    for result in reader.records() {
+        table.push_row(&result?);
+    }
+    Ok(table)
+}
+
+/// The `delimiter` argument of the CSV operations: a single ASCII character
+/// or `tab`, `,` when it is missing.
+fn delimiter(args: &Args) -> Result<u8, TransformError> {
+    match args.named("delimiter") {
+        None => Ok(b','),
+        Some("tab") => Ok(b'\t'),
+        Some(value) if value.len() == 1 && value.is_ascii() => Ok(value.as_bytes()[0]),
+        Some(value) => Err(TransformError::InvalidArgument(format!(
+            "Invalid delimiter: {} (expected a single ASCII character or tab)",
+            value
+        ))),
+    }
+}
@@ -0,0 +1,494 @@
+use super::{delimiter, read_records};
+use crate::args::Args;
+use crate::error::TransformError;
+use crate::operation::Operation;
+use crate::table::{is_numeric_column, Table, TableStyle};
+use csv::StringRecord;
+use std::cmp::Ordering;
+
+pub struct CsvQuery;
+
+impl Operation for CsvQuery {
+    fn name(&self) -> &'static str {
+        "csv-query"
+    }
+
+    fn description(&self) -> &'static str {
+        "Selects, filters, sorts and limits the rows of CSV data"
+    }
+
+    fn usage(&self) -> &'static str {
+        "csv-query(\"[select <col>, ...] [where <col> <op> <value> [and ...]] \
+         [sort by <col> [asc|desc], ...] [limit <n>]\"[, delimiter=<char>, border=unicode|ascii, bold=true, max-width=<n>]) <csv | @path>  (ops: = != < <= > >= ~ !~)"
+    }
+
+    fn run(&self, content: &str) -> Result<String, TransformError> {
+        self.run_with(content, &Args::default())
+    }
+
+    fn named_args(&self) -> &'static [&'static str] {
+        &["delimiter", "border", "bold", "max-width"]
+    }
+
+    fn run_with(&self, content: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 1, self.named_args())?;
+        let style = TableStyle::from_args(args)?;
+        Ok(table(content, args)?.render(&style))
+    }
+
+    fn output_bound(&self, content: &str, args: &Args) -> Option<usize> {
+        let style = TableStyle::from_args(args).ok()?;
+        Some(table(content, args).ok()?.rendered_len(&style))
+    }
+}
+
+fn table(content: &str, args: &Args) -> Result<Table, TransformError> {
+    let query = Query::parse(args.get(0).unwrap_or("")).map_err(TransformError::InvalidArgument)?;
+    let (headers, records) = read_records(content, delimiter(args)?)?;
+
+    let (headers, rows) = query
+        .execute(&headers, &records)
+        .map_err(TransformError::InvalidArgument)?;
+    let mut table = Table::new(headers);
+    for row in rows {
+        table.push_row(row);
+    }
+    Ok(table)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    NotContains,
+}
+
+#[derive(Debug, PartialEq)]
+struct Filter {
+    column: String,
+    comparison: Comparison,
+    value: String,
+}
+
+#[derive(Debug, PartialEq)]
+struct SortKey {
+    column: String,
+    descending: bool,
+}
+
+/// A parsed `csv-query` expression.
+#[derive(Debug, Default, PartialEq)]
+pub struct Query {
+    select: Vec<String>,
+    filters: Vec<Filter>,
+    sort: Vec<SortKey>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Word(String),
+    Quoted(String),
+    Comparison(Comparison),
+    Comma,
+}
+
+impl Token {
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            ',' => {
+                chars.next();
+                tokens.push(Token::Comma);
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some(next) if next == c => break,
+                        Some(next) => value.push(next),
+                        None => return Err(format!("Unterminated string: {}{}", c, value)),
+                    }
+                }
+                tokens.push(Token::Quoted(value));
+            }
+            '=' | '!' | '<' | '>' | '~' => {
+                chars.next();
+                let comparison = match (c, chars.next_if(|next| "=~".contains(*next))) {
+                    ('=', _) => Comparison::Eq,
+                    ('!', Some('=')) => Comparison::Ne,
+                    ('!', Some('~')) => Comparison::NotContains,
+                    ('<', Some('=')) => Comparison::Le,
+                    ('<', None) => Comparison::Lt,
+                    ('>', Some('=')) => Comparison::Ge,
+                    ('>', None) => Comparison::Gt,
+                    ('~', None) => Comparison::Contains,
+                    (c, Some(next)) => return Err(format!("Unknown operator: {}{}", c, next)),
+                    (c, None) => return Err(format!("Unknown operator: {}", c)),
+                };
+                tokens.push(Token::Comparison(comparison));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || ",\"'=!<>~".contains(next) {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Walks the tokens of a CSV command such as `csv-query` or `csv-group`.
+pub struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    pub fn new(input: &str) -> Result<Parser, String> {
+        Ok(Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        })
+    }
+
+    pub fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    pub fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    pub fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        let matched = self.peek().is_some_and(|token| token.is_keyword(keyword));
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+
+    pub fn next_if_comma(&mut self) -> bool {
+        let matched = self.peek() == Some(&Token::Comma);
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+
+    pub fn expect_value(&mut self, what: &str) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Word(value)) | Some(Token::Quoted(value)) => Ok(value),
+            Some(token) => Err(format!("Expected {}, found {:?}", what, token)),
+            None => Err(format!("Expected {}, found end of query", what)),
+        }
+    }
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Query, String> {
+        let mut parser = Parser::new(input)?;
+        let mut query = Query::default();
+
+        while let Some(token) = parser.next() {
+            if token.is_keyword("select") {
+                loop {
+                    query.select.push(parser.expect_value("a column name")?);
+                    if !parser.next_if_comma() {
+                        break;
+                    }
+                }
+            } else if token.is_keyword("where") {
+                loop {
+                    let column = parser.expect_value("a column name")?;
+                    let comparison = match parser.next() {
+                        Some(Token::Comparison(comparison)) => comparison,
+                        _ => return Err(format!("Expected an operator after '{}'", column)),
+                    };
+                    let value = parser.expect_value("a value")?;
+                    query.filters.push(Filter {
+                        column,
+                        comparison,
+                        value,
+                    });
+                    if !parser.next_if_keyword("and") {
+                        break;
+                    }
+                }
+            } else if token.is_keyword("sort") || token.is_keyword("order") {
+                if !parser.next_if_keyword("by") {
+                    return Err("Expected 'by' after 'sort'".to_string());
+                }
+                loop {
+                    let column = parser.expect_value("a column name")?;
+                    let descending = parser.next_if_keyword("desc");
+                    if !descending {
+                        parser.next_if_keyword("asc");
+                    }
+                    query.sort.push(SortKey { column, descending });
+                    if !parser.next_if_comma() {
+                        break;
+                    }
+                }
+            } else if token.is_keyword("limit") {
+                let limit = parser.expect_value("a row limit")?;
+                query.limit = Some(
+                    limit
+                        .parse()
+                        .map_err(|_| format!("Invalid limit: {}", limit))?,
+                );
+            } else {
+                return Err(format!("Unexpected token in query: {:?}", token));
+            }
+        }
+
+        Ok(query)
+    }
+
+    /// Applies the query to parsed records, returning the selected headers
+    /// and rows.
+    pub fn execute(
+        &self,
+        headers: &StringRecord,
+        records: &[StringRecord],
+    ) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+        let numeric: Vec<bool> = (0..headers.len())
+            .map(|i| is_numeric_column(records.iter().map(|record| record.get(i).unwrap_or(""))))
+            .collect();
+
+        let mut filters = Vec::new();
+        for filter in &self.filters {
+            let index = column_index(headers, &filter.column)?;
+            let is_ordering = !matches!(
+                filter.comparison,
+                Comparison::Contains | Comparison::NotContains
+            );
+            let number = match filter.value.parse::<f64>() {
+                Ok(number) if numeric[index] && is_ordering => Some(number),
+                Err(_) if numeric[index] && is_ordering => {
+                    return Err(format!(
+                        "Column '{}' is numeric, cannot compare it with '{}'",
+                        filter.column, filter.value
+                    ))
+                }
+                _ => None,
+            };
+            filters.push((index, filter, number));
+        }
+
+        let mut rows: Vec<&StringRecord> = records
+            .iter()
+            .filter(|record| {
+                filters.iter().all(|(index, filter, number)| {
+                    matches(record.get(*index).unwrap_or(""), filter, *number)
+                })
+            })
+            .collect();
+
+        let mut sort_keys = Vec::new();
+        for key in &self.sort {
+            let index = column_index(headers, &key.column)?;
+            sort_keys.push((index, key.descending, numeric[index]));
+        }
+        rows.sort_by(|a, b| {
+            sort_keys
+                .iter()
+                .map(|(index, descending, numeric)| {
+                    let (a, b) = (a.get(*index).unwrap_or(""), b.get(*index).unwrap_or(""));
+                    let ordering = if *numeric {
+                        let (a, b) = (a.parse::<f64>().ok(), b.parse::<f64>().ok());
+                        a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+                    } else {
+                        a.cmp(b)
+                    };
+                    if *descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        rows.truncate(self.limit.unwrap_or(rows.len()));
+
+        let selected = if self.select.is_empty() {
+            (0..headers.len()).collect()
+        } else {
+            self.select
+                .iter()
+                .map(|column| column_index(headers, column))
+                .collect::<Result<Vec<usize>, String>>()?
+        };
+
+        let project = |record: &StringRecord| {
+            selected
+                .iter()
+                .map(|i| record.get(*i).unwrap_or("").to_string())
+                .collect::<Vec<String>>()
+        };
+        Ok((project(headers), rows.into_iter().map(project).collect()))
+    }
+}
+
+/// Finds a column by exact name, falling back to a case-insensitive match.
+pub fn column_index(headers: &StringRecord, column: &str) -> Result<usize, String> {
+    headers
+        .iter()
+        .position(|header| header == column)
+        .or_else(|| {
+            headers
+                .iter()
+                .position(|header| header.eq_ignore_ascii_case(column))
+        })
+        .ok_or_else(|| {
+            format!(
+                "Unknown column '{}' (available: {})",
+                column,
+                headers.iter().collect::<Vec<&str>>().join(", ")
+            )
+        })
+}
+
+fn matches(cell: &str, filter: &Filter, number: Option<f64>) -> bool {
+    let ordering = match number {
+        Some(number) => match cell.trim().parse::<f64>() {
+            Ok(value) => value.partial_cmp(&number),
+            Err(_) => None,
+        },
+        None => Some(cell.cmp(filter.value.as_str())),
+    };
+
+    match filter.comparison {
+        Comparison::Contains => cell.to_lowercase().contains(&filter.value.to_lowercase()),
+        Comparison::NotContains => !cell.to_lowercase().contains(&filter.value.to_lowercase()),
+        Comparison::Eq => ordering == Some(Ordering::Equal),
+        Comparison::Ne => ordering != Some(Ordering::Equal),
+        Comparison::Lt => ordering == Some(Ordering::Less),
+        Comparison::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        Comparison::Gt => ordering == Some(Ordering::Greater),
+        Comparison::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn people() -> (StringRecord, Vec<StringRecord>) {
+        let headers = StringRecord::from(vec!["Name", "Age", "City"]);
+        let records = vec![
+            StringRecord::from(vec!["John Doe", "32", "New York"]),
+            StringRecord::from(vec!["Jane Smith", "28", "London"]),
+            StringRecord::from(vec!["Mario Rossi", "45", "Rome"]),
+            StringRecord::from(vec!["Joan Ng", "9", "London"]),
+        ];
+        (headers, records)
+    }
+
+    fn run(query: &str) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+        let (headers, records) = people();
+        Query::parse(query)?.execute(&headers, &records)
+    }
+
+    fn names(rows: &[Vec<String>]) -> Vec<&str> {
+        rows.iter().map(|row| row[0].as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_full_query() {
+        let query = Query::parse(
+            r#"select Name, Age where age>30 and Name ~ "Jo" sort by Age desc limit 5"#,
+        )
+        .unwrap();
+
+        assert_eq!(query.select, ["Name", "Age"]);
+        assert_eq!(
+            query.filters,
+            [
+                Filter {
+                    column: "age".to_string(),
+                    comparison: Comparison::Gt,
+                    value: "30".to_string()
+                },
+                Filter {
+                    column: "Name".to_string(),
+                    comparison: Comparison::Contains,
+                    value: "Jo".to_string()
+                }
+            ]
+        );
+        assert_eq!(
+            query.sort,
+            [SortKey {
+                column: "Age".to_string(),
+                descending: true
+            }]
+        );
+        assert_eq!(query.limit, Some(5));
+    }
+
+    #[test]
+    fn test_numeric_columns_compare_as_numbers() {
+        // "9" > "30" as strings, but not as numbers.
+        let (_, rows) = run("where Age > 30").unwrap();
+        assert_eq!(names(&rows), ["John Doe", "Mario Rossi"]);
+    }
+
+    #[test]
+    fn test_filter_sort_select_and_limit() {
+        let (headers, rows) = run("select Name where City = London sort by Age").unwrap();
+        assert_eq!(headers, ["Name"]);
+        assert_eq!(rows, [["Joan Ng"], ["Jane Smith"]]);
+
+        let (_, rows) = run("sort by City desc, Name limit 3").unwrap();
+        assert_eq!(names(&rows), ["Mario Rossi", "John Doe", "Jane Smith"]);
+    }
+
+    #[test]
+    fn test_contains_is_case_insensitive() {
+        let (_, rows) = run(r#"where name ~ "jo""#).unwrap();
+        assert_eq!(names(&rows), ["John Doe", "Joan Ng"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(run("where Salary > 3")
+            .unwrap_err()
+            .contains("Unknown column 'Salary'"));
+        assert!(run("where Age > old").unwrap_err().contains("is numeric"));
+        assert!(run("limit ten").unwrap_err().contains("Invalid limit"));
+        assert!(run(r#"where Name = "Jo"#)
+            .unwrap_err()
+            .contains("Unterminated"));
+        assert!(run("where City = Rome or Age > 3")
+            .unwrap_err()
+            .contains("Unexpected"));
+    }
+}
@@ -0,0 +1,292 @@
+use super::delimiter;
+use crate::args::Args;
+use crate::error::{TransformError, Violation};
+use crate::operation::Operation;
+use crate::operations::pattern::compile;
+use csv::StringRecord;
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+
+pub struct CsvValidate;
+
+impl Operation for CsvValidate {
+    fn name(&self) -> &'static str {
+        "csv-validate"
+    }
+
+    fn description(&self) -> &'static str {
+        "Checks every row of CSV data against a TOML schema"
+    }
+
+    fn usage(&self) -> &'static str {
+        "csv-validate(<schema path>[, delimiter=<char>]) <csv | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        self.run_with(input, &Args::default())
+    }
+
+    fn opens_files(&self) -> bool {
+        true
+    }
+
+    fn named_args(&self) -> &'static [&'static str] {
+        &["delimiter"]
+    }
+
+    fn run_with(&self, content: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 1, self.named_args())?;
+        let schema = Schema::load(args.required(0, "schema path")?)?;
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter(args)?)
+            .flexible(true)
+            .from_reader(content.as_bytes());
+        let headers = reader.headers()?.clone();
+        let mut violations = schema.check_headers(&headers);
+        let mut rows = 0;
+        for record in reader.records() {
+            violations.extend(schema.check_record(&headers, &record?));
+            rows += 1;
+        }
+
+        match violations.is_empty() {
+            true => Ok(format!("OK: {} row(s) match the schema", rows)),
+            false => Err(TransformError::Schema(violations)),
+        }
+    }
+}
+
+/// Rules for the columns of a CSV file, loaded from TOML:
+///
+/// ```toml
+/// allow_extra_columns = false
+///
+/// [[columns]]
+/// name = "age"
+/// type = "integer"     # string (default), integer, float or boolean
+/// required = true      # empty cells are violations
+/// pattern = "^[0-9]+$" # must match somewhere in the value
+/// values = ["1", "2"]  # the only allowed values
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schema {
+    #[serde(default)]
+    allow_extra_columns: bool,
+    columns: Vec<ColumnRule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColumnRule {
+    name: String,
+    #[serde(default, rename = "type")]
+    kind: ColumnType,
+    #[serde(default)]
+    required: bool,
+    #[serde(default, deserialize_with = "deserialize_pattern")]
+    pattern: Option<Regex>,
+    values: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ColumnType {
+    #[default]
+    String,
+    Integer,
+    Float,
+    Boolean,
+}
+
+fn deserialize_pattern<'de, D>(deserializer: D) -> Result<Option<Regex>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let pattern = String::deserialize(deserializer)?;
+    compile(&pattern)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+impl Schema {
+    pub fn load(path: &str) -> Result<Schema, TransformError> {
+        Schema::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Schema, TransformError> {
+        toml::from_str(text).map_err(|e| {
+            TransformError::InvalidArgument(format!("Invalid schema: {}", e.message()))
+        })
+    }
+
+    /// Reports schema columns missing from the header (only required ones)
+    /// and header columns the schema doesn't know about.
+    pub fn check_headers(&self, headers: &StringRecord) -> Vec<Violation> {
+        let violation = |column: &str, reason: &str| Violation {
+            line: 1,
+            column: Some(column.to_string()),
+            reason: reason.to_string(),
+        };
+
+        let missing = self
+            .columns
+            .iter()
+            .filter(|rule| rule.required && !headers.iter().any(|header| header == rule.name))
+            .map(|rule| violation(&rule.name, "Required column is missing"));
+        let unexpected = headers
+            .iter()
+            .filter(|header| !self.allow_extra_columns && self.rule(header).is_none())
+            .map(|header| violation(header, "Column is not in the schema"));
+
+        missing.chain(unexpected).collect()
+    }
+
+    /// Checks every cell of `record` against the rule for its column.
+    pub fn check_record(&self, headers: &StringRecord, record: &StringRecord) -> Vec<Violation> {
+        let line = record.position().map_or(0, |position| position.line());
+        if record.len() != headers.len() {
+            return vec![Violation {
+                line,
+                column: None,
+                reason: format!("Expected {} fields, found {}", headers.len(), record.len()),
+            }];
+        }
+
+        headers
+            .iter()
+            .zip(record.iter())
+            .filter_map(|(header, value)| {
+                let reason = self.rule(header)?.check(value)?;
+                Some(Violation {
+                    line,
+                    column: Some(header.to_string()),
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    fn rule(&self, column: &str) -> Option<&ColumnRule> {
+        self.columns.iter().find(|rule| rule.name == column)
+    }
+}
+
+impl ColumnRule {
+    /// Returns why `value` breaks the rule, if it does. Empty cells only
+    /// fail when the column is required.
+    fn check(&self, value: &str) -> Option<String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return self.required.then(|| "Value is required".to_string());
+        }
+
+        let valid_type = match self.kind {
+            ColumnType::String => true,
+            ColumnType::Integer => value.parse::<i64>().is_ok(),
+            ColumnType::Float => value.parse::<f64>().is_ok(),
+            ColumnType::Boolean => matches!(value, "true" | "false"),
+        };
+        if !valid_type {
+            let expected = match self.kind {
+                ColumnType::String => "a string",
+                ColumnType::Integer => "an integer",
+                ColumnType::Float => "a number",
+                ColumnType::Boolean => "true or false",
+            };
+            return Some(format!("Expected {}, found '{}'", expected, value));
+        }
+
+        if let Some(pattern) = &self.pattern {
+            if !pattern.is_match(value) {
+                return Some(format!("'{}' does not match pattern '{}'", value, pattern));
+            }
+        }
+
+        match &self.values {
+            Some(values) if !values.iter().any(|allowed| allowed == value) => Some(format!(
+                "Expected one of {}, found '{}'",
+                values.join(", "),
+                value
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+        [[columns]]
+        name = "id"
+        type = "integer"
+        required = true
+
+        [[columns]]
+        name = "email"
+        pattern = "^[^@]+@[^@]+$"
+
+        [[columns]]
+        name = "plan"
+        values = ["free", "pro"]
+    "#;
+
+    fn check(data: &str) -> Vec<String> {
+        let schema = Schema::parse(SCHEMA).unwrap();
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(data.as_bytes());
+        let headers = reader.headers().unwrap().clone();
+
+        let mut violations = schema.check_headers(&headers);
+        for record in reader.records() {
+            violations.extend(schema.check_record(&headers, &record.unwrap()));
+        }
+        violations.iter().map(Violation::to_string).collect()
+    }
+
+    #[test]
+    fn test_valid_rows_pass() {
+        assert!(check("id,email,plan\n1,a@b.cz,pro\n2,,\n").is_empty());
+    }
+
+    #[test]
+    fn test_every_violation_is_reported() {
+        assert_eq!(
+            check("id,email,plan\nx,nope,gold\n,a@b,free\n3\n"),
+            [
+                "line 2, column 'id': Expected an integer, found 'x'",
+                "line 2, column 'email': 'nope' does not match pattern '^[^@]+@[^@]+$'",
+                "line 2, column 'plan': Expected one of free, pro, found 'gold'",
+                "line 3, column 'id': Value is required",
+                "line 4: Expected 3 fields, found 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_header_violations() {
+        assert_eq!(
+            check("email,extra\n"),
+            [
+                "line 1, column 'id': Required column is missing",
+                "line 1, column 'extra': Column is not in the schema",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_schema() {
+        let err = Schema::parse("[[columns]]\nname = \"a\"\ntype = \"date\"").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Invalid schema: unknown variant `date`"));
+
+        let err = Schema::parse("[[columns]]\nname = \"a\"\npattern = \"(\"").unwrap_err();
+        assert!(err.to_string().contains("Invalid pattern '('"));
+    }
+}
@@ -0,0 +1,357 @@
+use crate::args::Args;
+use crate::error::TransformError;
+use crate::operation::Operation;
+use crate::source::Source;
+use data_encoding::{BASE32, BASE64};
+use std::io::Read;
+
+/// Encodes the UTF-8 bytes of the text, or the raw bytes of a file or stdin.
+pub struct Encode {
+    name: &'static str,
+    description: &'static str,
+    usage: &'static str,
+    encode: fn(&[u8]) -> String,
+}
+
+impl Operation for Encode {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn usage(&self) -> &'static str {
+        self.usage
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        Ok((self.encode)(input.as_bytes()))
+    }
+
+    fn run_source(&self, source: Source, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name, 0, &[])?;
+
+        let mut bytes = Vec::new();
+        source.open()?.read_to_end(&mut bytes)?;
+        Ok((self.encode)(&bytes))
+    }
+}
+
+/// Decodes the input back into bytes, which must form valid UTF-8 text.
+pub struct Decode {
+    name: &'static str,
+    description: &'static str,
+    usage: &'static str,
+    decode: fn(&str) -> Result<Decoded, TransformError>,
+}
+
+/// Decoded bytes along with the input offset each of them was read from.
+type Decoded = (Vec<u8>, Vec<usize>);
+
+impl Operation for Decode {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn usage(&self) -> &'static str {
+        self.usage
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        // Offsets are reported relative to the untrimmed input.
+        let trimmed = input.trim();
+        let leading = input.len() - input.trim_start().len();
+        let (bytes, offsets) = (self.decode)(trimmed).map_err(|err| match err {
+            TransformError::Encoding { offset, message } => TransformError::Encoding {
+                offset: offset + leading,
+                message,
+            },
+            other => other,
+        })?;
+
+        String::from_utf8(bytes).map_err(|e| TransformError::Encoding {
+            offset: offsets[e.utf8_error().valid_up_to()] + leading,
+            message: "Decoded bytes are not valid UTF-8".to_string(),
+        })
+    }
+}
+
+pub fn operations() -> Vec<Box<dyn Operation>> {
+    vec![
+        Box::new(Encode {
+            name: "binary",
+            description: "Prints each UTF-8 byte as an 8-bit binary number",
+            usage: "binary <text | @path>",
+            encode: to_binary,
+        }),
+        Box::new(Decode {
+            name: "from-binary",
+            description: "Decodes 8-bit binary numbers back into text",
+            usage: "from-binary <bits | @path>",
+            decode: from_binary,
+        }),
+        Box::new(Encode {
+            name: "hex",
+            description: "Encodes the UTF-8 bytes as hexadecimal",
+            usage: "hex <text | @path>",
+            encode: to_hex,
+        }),
+        Box::new(Decode {
+            name: "from-hex",
+            description: "Decodes hexadecimal back into text",
+            usage: "from-hex <hex digits | @path>",
+            decode: from_hex,
+        }),
+        Box::new(Encode {
+            name: "base64",
+            description: "Encodes the UTF-8 bytes as base64",
+            usage: "base64 <text | @path>",
+            encode: |bytes| BASE64.encode(bytes),
+        }),
+        Box::new(Decode {
+            name: "from-base64",
+            description: "Decodes base64 back into text",
+            usage: "from-base64 <base64 | @path>",
+            decode: |input| decode_with(&BASE64, input),
+        }),
+        Box::new(Encode {
+            name: "base32",
+            description: "Encodes the UTF-8 bytes as base32",
+            usage: "base32 <text | @path>",
+            encode: |bytes| BASE32.encode(bytes),
+        }),
+        Box::new(Decode {
+            name: "from-base32",
+            description: "Decodes base32 back into text",
+            usage: "from-base32 <base32 | @path>",
+            decode: |input| decode_with(&BASE32, input),
+        }),
+        Box::new(Encode {
+            name: "percent-encode",
+            description: "Percent-encodes everything except unreserved URL characters",
+            usage: "percent-encode <text | @path>",
+            encode: percent_encode,
+        }),
+        Box::new(Decode {
+            name: "percent-decode",
+            description: "Decodes %XX escapes back into text",
+            usage: "percent-decode <text | @path>",
+            decode: percent_decode,
+        }),
+    ]
+}
+
+fn to_binary(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:08b}", byte))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Reads digits of the given radix, `digits_per_byte` at a time, skipping
+/// whitespace between them.
+fn decode_digits(
+    input: &str,
+    radix: u32,
+    digits_per_byte: usize,
+) -> Result<Decoded, TransformError> {
+    let mut bytes = Vec::new();
+    let mut offsets = Vec::new();
+    let mut byte = 0u32;
+    let mut digits = 0;
+    let mut byte_start = 0;
+
+    for (offset, c) in input.char_indices() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let digit = c.to_digit(radix).ok_or_else(|| TransformError::Encoding {
+            offset,
+            message: format!("Invalid base-{} digit '{}'", radix, c),
+        })?;
+        if digits == 0 {
+            byte_start = offset;
+        }
+        byte = byte * radix + digit;
+        digits += 1;
+        if digits == digits_per_byte {
+            bytes.push(byte as u8);
+            offsets.push(byte_start);
+            byte = 0;
+            digits = 0;
+        }
+    }
+
+    if digits != 0 {
+        return Err(TransformError::Encoding {
+            offset: byte_start,
+            message: "Incomplete byte".to_string(),
+        });
+    }
+    Ok((bytes, offsets))
+}
+
+fn from_binary(input: &str) -> Result<Decoded, TransformError> {
+    decode_digits(input, 2, 8)
+}
+
+fn from_hex(input: &str) -> Result<Decoded, TransformError> {
+    decode_digits(input, 16, 2)
+}
+
+/// Decodes base64 or base32. Each byte is attributed to the symbol holding
+/// its first bit.
+fn decode_with(encoding: &data_encoding::Encoding, input: &str) -> Result<Decoded, TransformError> {
+    let bytes = encoding
+        .decode(input.as_bytes())
+        .map_err(|e| TransformError::Encoding {
+            offset: e.position,
+            message: e.kind.to_string(),
+        })?;
+    let offsets = (0..bytes.len())
+        .map(|index| index * 8 / encoding.bit_width())
+        .collect();
+    Ok((bytes, offsets))
+}
+
+fn percent_encode(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn percent_decode(input: &str) -> Result<Decoded, TransformError> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut offsets = Vec::with_capacity(bytes.len());
+    let mut offset = 0;
+
+    while offset < bytes.len() {
+        offsets.push(offset);
+        if bytes[offset] != b'%' {
+            decoded.push(bytes[offset]);
+            offset += 1;
+            continue;
+        }
+        // `from_str_radix` alone would also accept a sign, as in `%+1`.
+        let escape = bytes
+            .get(offset + 1..offset + 3)
+            .filter(|digits| digits.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| TransformError::Encoding {
+                offset,
+                message: "Invalid percent escape".to_string(),
+            })?;
+        decoded.push(escape);
+        offset += 3;
+    }
+
+    Ok((decoded, offsets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(name: &str, input: &str) -> Result<String, String> {
+        operations()
+            .into_iter()
+            .find(|operation| operation.name() == name)
+            .unwrap()
+            .run(input)
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_encodings_use_utf8_bytes() {
+        assert_eq!(run("binary", "é").unwrap(), "11000011 10101001");
+        assert_eq!(run("hex", "Hé").unwrap(), "48c3a9");
+        assert_eq!(run("base64", "Hé").unwrap(), "SMOp");
+        assert_eq!(run("base32", "Hé").unwrap(), "JDB2S===");
+        assert_eq!(run("percent-encode", "a b/é").unwrap(), "a%20b%2F%C3%A9");
+    }
+
+    #[test]
+    fn test_decodings_round_trip() {
+        for (encode, decode) in [
+            ("binary", "from-binary"),
+            ("hex", "from-hex"),
+            ("base64", "from-base64"),
+            ("base32", "from-base32"),
+            ("percent-encode", "percent-decode"),
+        ] {
+            let encoded = run(encode, "Žluť 🦀").unwrap();
+            assert_eq!(run(decode, &encoded).unwrap(), "Žluť 🦀", "{}", decode);
+        }
+    }
+
+    #[test]
+    fn test_decode_errors_report_offsets() {
+        assert_eq!(
+            run("from-binary", "01000001 0100x001").unwrap_err(),
+            "Invalid base-2 digit 'x' at byte offset 13"
+        );
+        assert_eq!(
+            run("from-hex", "48c").unwrap_err(),
+            "Incomplete byte at byte offset 2"
+        );
+        assert_eq!(
+            run("from-base64", "SM*p").unwrap_err(),
+            "invalid symbol at byte offset 2"
+        );
+        assert_eq!(
+            run("percent-decode", "a%2").unwrap_err(),
+            "Invalid percent escape at byte offset 1"
+        );
+        assert_eq!(
+            run("percent-decode", "%+1").unwrap_err(),
+            "Invalid percent escape at byte offset 0"
+        );
+    }
+
+    #[test]
+    fn test_invalid_utf8_offsets_point_into_the_input() {
+        assert_eq!(
+            run("from-hex", "48ff").unwrap_err(),
+            "Decoded bytes are not valid UTF-8 at byte offset 2"
+        );
+        assert_eq!(
+            run("from-hex", " 48 ff").unwrap_err(),
+            "Decoded bytes are not valid UTF-8 at byte offset 4"
+        );
+        assert_eq!(
+            run("from-binary", "01001000 11111111").unwrap_err(),
+            "Decoded bytes are not valid UTF-8 at byte offset 9"
+        );
+        assert_eq!(
+            run("from-base64", "SP8=").unwrap_err(),
+            "Decoded bytes are not valid UTF-8 at byte offset 1"
+        );
+        assert_eq!(
+            run("percent-decode", "a%2Fb%FF").unwrap_err(),
+            "Decoded bytes are not valid UTF-8 at byte offset 5"
+        );
+        assert_eq!(
+            run("from-hex", "  48zz").unwrap_err(),
+            "Invalid base-16 digit 'z' at byte offset 4"
+        );
+    }
+}
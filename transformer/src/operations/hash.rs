@@ -0,0 +1,173 @@
+use crate::args::Args;
+use crate::error::TransformError;
+use crate::operation::Operation;
+use crate::source::Source;
+use std::io::Read;
+
+/// Incrementally computes a digest, so files can be hashed without reading
+/// them into memory.
+pub trait Checksum {
+    fn update(&mut self, bytes: &[u8]);
+
+    /// The digest as lowercase hex.
+    fn finish(self: Box<Self>) -> String;
+}
+
+/// Adapts the RustCrypto hashers, which all share the `Digest` trait.
+struct Crypto<D>(D);
+
+impl<D: sha2::Digest> Checksum for Crypto<D> {
+    fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(self: Box<Self>) -> String {
+        self.0
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+impl Checksum for blake3::Hasher {
+    fn update(&mut self, bytes: &[u8]) {
+        blake3::Hasher::update(self, bytes);
+    }
+
+    fn finish(self: Box<Self>) -> String {
+        self.finalize().to_hex().to_string()
+    }
+}
+
+impl Checksum for crc32fast::Hasher {
+    fn update(&mut self, bytes: &[u8]) {
+        crc32fast::Hasher::update(self, bytes);
+    }
+
+    fn finish(self: Box<Self>) -> String {
+        format!("{:08x}", self.finalize())
+    }
+}
+
+/// Hashes the UTF-8 bytes of the text, or the raw bytes of a file or stdin,
+/// which are streamed rather than read into memory.
+pub struct Hash {
+    name: &'static str,
+    description: &'static str,
+    usage: &'static str,
+    hasher: fn() -> Box<dyn Checksum>,
+}
+
+impl Operation for Hash {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn usage(&self) -> &'static str {
+        self.usage
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        let mut hasher = (self.hasher)();
+        hasher.update(input.as_bytes());
+        Ok(hasher.finish())
+    }
+
+    fn run_source(&self, source: Source, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name, 0, &[])?;
+
+        let mut hasher = (self.hasher)();
+        hash_reader(hasher.as_mut(), source.open()?)?;
+        Ok(hasher.finish())
+    }
+}
+
+/// Feeds `reader` to `hasher` in fixed-size chunks.
+pub fn hash_reader(hasher: &mut dyn Checksum, mut reader: impl Read) -> Result<(), TransformError> {
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        match reader.read(&mut buffer)? {
+            0 => return Ok(()),
+            read => hasher.update(&buffer[..read]),
+        }
+    }
+}
+
+pub fn operations() -> Vec<Box<dyn Operation>> {
+    vec![
+        Box::new(Hash {
+            name: "sha256",
+            description: "Prints the SHA-256 digest of the text or a file",
+            usage: "sha256 <text | @path>",
+            hasher: || Box::new(Crypto(sha2::Sha256::default())),
+        }),
+        Box::new(Hash {
+            name: "sha1",
+            description: "Prints the SHA-1 digest of the text or a file",
+            usage: "sha1 <text | @path>",
+            hasher: || Box::new(Crypto(sha1::Sha1::default())),
+        }),
+        Box::new(Hash {
+            name: "md5",
+            description: "Prints the MD5 digest of the text or a file",
+            usage: "md5 <text | @path>",
+            hasher: || Box::new(Crypto(md5::Md5::default())),
+        }),
+        Box::new(Hash {
+            name: "blake3",
+            description: "Prints the BLAKE3 digest of the text or a file",
+            usage: "blake3 <text | @path>",
+            hasher: || Box::new(blake3::Hasher::new()),
+        }),
+        Box::new(Hash {
+            name: "crc32",
+            description: "Prints the CRC-32 checksum of the text or a file",
+            usage: "crc32 <text | @path>",
+            hasher: || Box::new(crc32fast::Hasher::new()),
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn digest(name: &str, input: &str) -> String {
+        let operations = operations();
+        let operation = operations.iter().find(|op| op.name() == name).unwrap();
+        operation.run(input).unwrap()
+    }
+
+    #[test]
+    fn test_known_digests() {
+        assert_eq!(
+            digest("sha256", "abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            digest("sha1", "abc"),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(digest("md5", "abc"), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(
+            digest("blake3", "abc"),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+        assert_eq!(digest("crc32", "123456789"), "cbf43926");
+    }
+
+    #[test]
+    fn test_streamed_hash_matches_one_shot() {
+        let data = "chunk ".repeat(20_000);
+        let mut hasher: Box<dyn Checksum> = Box::new(Crypto(sha2::Sha256::default()));
+        hash_reader(hasher.as_mut(), Cursor::new(data.as_bytes())).unwrap();
+
+        assert_eq!(hasher.finish(), digest("sha256", &data));
+    }
+}
@@ -0,0 +1,378 @@
+use crate::args::Args;
+use crate::error::TransformError;
+use crate::operation::Operation;
+use serde_json::Value;
+use std::io;
+
+pub struct JsonPretty;
+
+impl Operation for JsonPretty {
+    fn name(&self) -> &'static str {
+        "json-pretty"
+    }
+
+    fn description(&self) -> &'static str {
+        "Pretty-prints a JSON document, keeping the key order"
+    }
+
+    fn usage(&self) -> &'static str {
+        "json-pretty <json | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        let value: Value = serde_json::from_str(input)?;
+        Ok(serde_json::to_string_pretty(&value)?)
+    }
+
+    /// Indentation grows with the depth, so deeply nested documents can get
+    /// much longer.
+    fn output_bound(&self, input: &str, _args: &Args) -> Option<usize> {
+        let value: Value = serde_json::from_str(input).ok()?;
+        let mut count = ByteCount(0);
+        serde_json::to_writer_pretty(&mut count, &value).ok()?;
+        Some(count.0)
+    }
+}
+
+pub struct JsonMinify;
+
+impl Operation for JsonMinify {
+    fn name(&self) -> &'static str {
+        "json-minify"
+    }
+
+    fn description(&self) -> &'static str {
+        "Removes all insignificant whitespace from a JSON document"
+    }
+
+    fn usage(&self) -> &'static str {
+        "json-minify <json | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        let value: Value = serde_json::from_str(input)?;
+        Ok(serde_json::to_string(&value)?)
+    }
+}
+
+pub struct JsonQuery;
+
+impl Operation for JsonQuery {
+    fn name(&self) -> &'static str {
+        "json-query"
+    }
+
+    fn description(&self) -> &'static str {
+        "Selects values from a JSON document with a path like $[*].data.filename"
+    }
+
+    fn usage(&self) -> &'static str {
+        "json-query(<path>[, raw=true]) <json | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        self.run_with(input, &Args::default())
+    }
+
+    fn named_args(&self) -> &'static [&'static str] {
+        &["raw"]
+    }
+
+    fn run_with(&self, input: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 1, self.named_args())?;
+        let path =
+            JsonPath::parse(args.required(0, "path")?).map_err(TransformError::InvalidArgument)?;
+        let raw = args.named_bool("raw")?;
+        let value: Value = serde_json::from_str(input)?;
+
+        let lines: Vec<String> = path
+            .select(&value)
+            .into_iter()
+            .map(|found| match found {
+                Value::String(text) if raw => text.clone(),
+                other => other.to_string(),
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+
+    /// `..key` can select a value and values nested inside it, so the same
+    /// text can be printed many times.
+    fn output_bound(&self, input: &str, args: &Args) -> Option<usize> {
+        let path = JsonPath::parse(args.get(0)?).ok()?;
+        let value: Value = serde_json::from_str(input).ok()?;
+        let mut count = ByteCount(0);
+        for found in path.select(&value) {
+            serde_json::to_writer(&mut count, found).ok()?;
+            count.0 += 1;
+        }
+        Some(count.0)
+    }
+}
+
+/// Counts the bytes written to it, to measure output without building it.
+struct ByteCount(usize);
+
+impl io::Write for ByteCount {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    /// `.name` or `['name']`
+    Key(String),
+    /// `[2]`, or `[-1]` counting from the end
+    Index(i64),
+    /// `[*]` or `.*`: every array element or object value
+    Wildcard,
+    /// `..name`: `name` at any depth
+    Descendant(String),
+}
+
+/// A JSONPath-like selector: `$` followed by `.key`, `['key']`, `[index]`,
+/// `[*]`, `.*` and `..key` segments.
+#[derive(Debug, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<JsonPath, String> {
+        let mut rest = path
+            .trim()
+            .strip_prefix('$')
+            .ok_or("Path must start with '$'")?;
+        let mut segments = Vec::new();
+
+        while !rest.is_empty() {
+            // 1-based position of the segment in the path, for errors.
+            let position = path.trim().chars().count() - rest.chars().count() + 1;
+
+            if let Some(after) = rest.strip_prefix("..") {
+                let (name, after) = split_name(after);
+                if name.is_empty() {
+                    return Err(format!(
+                        "Expected a name after '..' at character {}",
+                        position
+                    ));
+                }
+                segments.push(Segment::Descendant(name.to_string()));
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix('.') {
+                let (name, after) = split_name(after);
+                segments.push(match name {
+                    "" => {
+                        return Err(format!(
+                            "Expected a name after '.' at character {}",
+                            position
+                        ))
+                    }
+                    "*" => Segment::Wildcard,
+                    name => Segment::Key(name.to_string()),
+                });
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let end = after
+                    .find(']')
+                    .ok_or_else(|| format!("Missing ']' for '[' at character {}", position))?;
+                segments.push(parse_bracket(after[..end].trim()).ok_or_else(|| {
+                    format!(
+                        "Expected '*', an index or a quoted key in '[{}]' at character {}",
+                        &after[..end],
+                        position
+                    )
+                })?);
+                rest = &after[end + 1..];
+            } else {
+                let c = rest.chars().next().unwrap_or_default();
+                return Err(format!("Unexpected '{}' at character {}", c, position));
+            }
+        }
+
+        Ok(JsonPath { segments })
+    }
+
+    /// Every value the path matches, in document order.
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![root];
+
+        for segment in &self.segments {
+            current = current
+                .into_iter()
+                .flat_map(|value| -> Vec<&Value> {
+                    match (segment, value) {
+                        (Segment::Key(key), Value::Object(map)) => {
+                            map.get(key).into_iter().collect()
+                        }
+                        (Segment::Index(index), Value::Array(items)) => {
+                            let index = match *index < 0 {
+                                true => items.len().checked_sub(index.unsigned_abs() as usize),
+                                false => Some(*index as usize),
+                            };
+                            index
+                                .and_then(|index| items.get(index))
+                                .into_iter()
+                                .collect()
+                        }
+                        (Segment::Wildcard, Value::Array(items)) => items.iter().collect(),
+                        (Segment::Wildcard, Value::Object(map)) => map.values().collect(),
+                        (Segment::Descendant(key), value) => {
+                            let mut found = Vec::new();
+                            descendants(value, key, &mut found);
+                            found
+                        }
+                        _ => Vec::new(),
+                    }
+                })
+                .collect();
+        }
+
+        current
+    }
+}
+
+/// Splits off a dotted name, which runs until the next `.` or `[`.
+fn split_name(text: &str) -> (&str, &str) {
+    let end = text.find(['.', '[']).unwrap_or(text.len());
+    text.split_at(end)
+}
+
+fn parse_bracket(content: &str) -> Option<Segment> {
+    if content == "*" {
+        return Some(Segment::Wildcard);
+    }
+    if let Ok(index) = content.parse() {
+        return Some(Segment::Index(index));
+    }
+
+    let quote = content.chars().next().filter(|c| matches!(c, '\'' | '"'))?;
+    let key = content.strip_prefix(quote)?.strip_suffix(quote)?;
+    Some(Segment::Key(key.to_string()))
+}
+
+/// Collects `key` from `value` and everything nested in it, depth first.
+fn descendants<'a>(value: &'a Value, key: &str, found: &mut Vec<&'a Value>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(child) = map.get(key) {
+                found.push(child);
+            }
+            for child in map.values() {
+                descendants(child, key, found);
+            }
+        }
+        Value::Array(items) => {
+            for child in items {
+                descendants(child, key, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn messages() -> Value {
+        json!([
+            {"id": 1, "data": {"type": "text", "text": "hi"}},
+            {"id": 2, "data": {"type": "file", "filename": "a.png"}},
+            {"id": 3, "data": {"type": "file", "filename": "b.pdf"}},
+        ])
+    }
+
+    fn query(path: &str) -> Vec<Value> {
+        let value = messages();
+        JsonPath::parse(path)
+            .unwrap()
+            .select(&value)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_select_paths() {
+        assert_eq!(
+            query("$[*].data.filename"),
+            [json!("a.png"), json!("b.pdf")]
+        );
+        assert_eq!(query("$[-1]['data'].type"), [json!("file")]);
+        assert_eq!(query("$[0].data.*"), [json!("text"), json!("hi")]);
+        assert_eq!(query("$..filename"), [json!("a.png"), json!("b.pdf")]);
+        assert_eq!(query("$[5].id"), Vec::<Value>::new());
+        assert_eq!(query("$"), [messages()]);
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_segment() {
+        assert_eq!(
+            JsonPath::parse("data").unwrap_err(),
+            "Path must start with '$'"
+        );
+        assert_eq!(
+            JsonPath::parse("$.data.").unwrap_err(),
+            "Expected a name after '.' at character 7"
+        );
+        assert_eq!(
+            JsonPath::parse("$[1].x[abc]").unwrap_err(),
+            "Expected '*', an index or a quoted key in '[abc]' at character 7"
+        );
+        assert_eq!(
+            JsonPath::parse("$[0").unwrap_err(),
+            "Missing ']' for '[' at character 2"
+        );
+    }
+
+    #[test]
+    fn test_query_raw_strings() {
+        let (args, _) = Args::parse("('$[*].data.filename', raw=true)").unwrap();
+        let input = messages().to_string();
+
+        assert_eq!(JsonQuery.run_with(&input, &args).unwrap(), "a.png\nb.pdf");
+    }
+
+    #[test]
+    fn test_pretty_and_minify_keep_key_order() {
+        let input = r#"{ "b": 1,  "a": [true, null] }"#;
+
+        assert_eq!(JsonMinify.run(input).unwrap(), r#"{"b":1,"a":[true,null]}"#);
+        assert_eq!(
+            JsonPretty.run(input).unwrap(),
+            "{\n  \"b\": 1,\n  \"a\": [\n    true,\n    null\n  ]\n}"
+        );
+    }
+
+    #[test]
+    fn test_pretty_and_minify_keep_numbers_as_written() {
+        let input = r#"{ "id": 123456789012345678901234567890, "x": 1.50 }"#;
+
+        assert_eq!(
+            JsonMinify.run(input).unwrap(),
+            r#"{"id":123456789012345678901234567890,"x":1.50}"#
+        );
+        assert_eq!(
+            JsonPretty.run(input).unwrap(),
+            "{\n  \"id\": 123456789012345678901234567890,\n  \"x\": 1.50\n}"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let err = JsonMinify.run("{\"a\": 1,\n  \"b\" 2}").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "JSON error on line 2, column 7: expected `:`"
+        );
+    }
+}
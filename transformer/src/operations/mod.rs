@@ -0,0 +1,54 @@
+use crate::operation::Registry;
+
+mod case;
+mod csv;
+mod encoding;
+mod hash;
+mod json;
+mod pattern;
+mod stats;
+mod text;
+mod unicode;
+mod wrap;
+
+/// Builds a registry containing every built-in operation.
+pub fn default_registry() -> Registry {
+    let mut registry = Registry::new();
+
+    registry.register(Box::new(case::Lowercase));
+    registry.register(Box::new(case::Uppercase));
+    registry.register(Box::new(text::NoSpaces));
+    registry.register(Box::new(text::Slugify));
+    registry.register(Box::new(text::Reverse));
+    registry.register(Box::new(pattern::Replace));
+    registry.register(Box::new(pattern::Extract));
+    registry.register(Box::new(pattern::Split));
+    registry.register(Box::new(stats::Stats));
+    registry.register(Box::new(wrap::Wrap));
+    registry.register(Box::new(csv::Csv));
+    registry.register(Box::new(csv::query::CsvQuery));
+    registry.register(Box::new(csv::aggregate::CsvGroup));
+    registry.register(Box::new(csv::convert::CsvToJson));
+    registry.register(Box::new(csv::convert::JsonToCsv));
+    registry.register(Box::new(csv::convert::CsvToMarkdown));
+    registry.register(Box::new(csv::convert::CsvToHtml));
+    registry.register(Box::new(csv::validate::CsvValidate));
+    registry.register(Box::new(json::JsonPretty));
+    registry.register(Box::new(json::JsonMinify));
+    registry.register(Box::new(json::JsonQuery));
+
+    for operation in case::conversions() {
+        registry.register(operation);
+    }
+    for operation in encoding::operations() {
+        registry.register(operation);
+    }
+    for operation in hash::operations() {
+        registry.register(operation);
+    }
+    for operation in unicode::operations() {
+        registry.register(operation);
+    }
+
+    registry
+}
@@ -0,0 +1,243 @@
+use crate::args::Args;
+use crate::error::TransformError;
+use crate::operation::Operation;
+use regex::Regex;
+
+pub struct Replace;
+
+impl Operation for Replace {
+    fn name(&self) -> &'static str {
+        "replace"
+    }
+
+    fn description(&self) -> &'static str {
+        "Replaces regex matches, expanding $1 and ${name} in the replacement"
+    }
+
+    fn usage(&self) -> &'static str {
+        "replace(<pattern>, <replacement>[, limit=<n>]) <text | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        self.run_with(input, &Args::default())
+    }
+
+    fn named_args(&self) -> &'static [&'static str] {
+        &["limit"]
+    }
+
+    fn run_with(&self, input: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 2, self.named_args())?;
+        let regex = compile(args.required(0, "pattern")?)?;
+        let replacement = args.required(1, "replacement")?;
+        let limit = args.named_usize("limit")?.unwrap_or(0);
+
+        Ok(regex.replacen(input, limit, replacement).into_owned())
+    }
+
+    /// Every match adds the replacement, and each `$` reference in it adds
+    /// at most the match itself; all matches together are at most the input.
+    fn output_bound(&self, input: &str, args: &Args) -> Option<usize> {
+        let regex = compile(args.get(0)?).ok()?;
+        let replacement = args.get(1)?;
+        let matches = match args.named_usize("limit").ok()?.unwrap_or(0) {
+            0 => regex.find_iter(input).count(),
+            limit => regex.find_iter(input).take(limit).count(),
+        };
+        let references = replacement.matches('$').count();
+
+        Some(
+            input
+                .len()
+                .saturating_mul(references + 1)
+                .saturating_add(matches.saturating_mul(replacement.len())),
+        )
+    }
+}
+
+pub struct Extract;
+
+impl Operation for Extract {
+    fn name(&self) -> &'static str {
+        "extract"
+    }
+
+    fn description(&self) -> &'static str {
+        "Prints every regex match, or one capture group of it, on its own line"
+    }
+
+    fn usage(&self) -> &'static str {
+        "extract(<pattern>[, <group number or name>]) <text | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        self.run_with(input, &Args::default())
+    }
+
+    fn run_with(&self, input: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 2, &[])?;
+        let regex = compile(args.required(0, "pattern")?)?;
+        let group = args.get(1).unwrap_or("0");
+
+        let missing = || TransformError::InvalidArgument(format!("Pattern has no group {}", group));
+        let matches: Vec<&str> = match group.parse::<usize>() {
+            Ok(index) if index >= regex.captures_len() => return Err(missing()),
+            Ok(index) => regex
+                .captures_iter(input)
+                .filter_map(|captures| captures.get(index))
+                .map(|found| found.as_str())
+                .collect(),
+            Err(_) if !regex.capture_names().any(|name| name == Some(group)) => {
+                return Err(missing())
+            }
+            Err(_) => regex
+                .captures_iter(input)
+                .filter_map(|captures| captures.name(group))
+                .map(|found| found.as_str())
+                .collect(),
+        };
+
+        Ok(matches.join("\n"))
+    }
+}
+
+pub struct Split;
+
+impl Operation for Split {
+    fn name(&self) -> &'static str {
+        "split"
+    }
+
+    fn description(&self) -> &'static str {
+        "Splits the text on regex matches, printing one part per line"
+    }
+
+    fn usage(&self) -> &'static str {
+        "split(<pattern>[, limit=<n>]) <text | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        self.run_with(input, &Args::default())
+    }
+
+    fn named_args(&self) -> &'static [&'static str] {
+        &["limit"]
+    }
+
+    fn run_with(&self, input: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 1, self.named_args())?;
+        let regex = compile(args.required(0, "pattern")?)?;
+
+        let parts: Vec<&str> = match args.named_usize("limit")? {
+            Some(limit) => regex.splitn(input, limit).collect(),
+            None => regex.split(input).collect(),
+        };
+        Ok(parts.join("\n"))
+    }
+}
+
+/// Compiles `pattern`, condensing regex's multi-line syntax errors (which
+/// point at the problem with carets) into one line with a position.
+pub fn compile(pattern: &str) -> Result<Regex, TransformError> {
+    Regex::new(pattern).map_err(|err| {
+        let regex::Error::Syntax(details) = &err else {
+            return TransformError::InvalidArgument(format!("Invalid pattern: {}", err));
+        };
+
+        let reason = details
+            .lines()
+            .last()
+            .unwrap_or_default()
+            .trim_start_matches("error: ");
+        // The pattern is echoed with a four space indent, followed by a line
+        // of carets under the offending part.
+        let position = details
+            .lines()
+            .find(|line| line.trim_start().starts_with('^'))
+            .and_then(|line| line.find('^'))
+            .filter(|_| !pattern.contains('\n'))
+            .map(|column| column.saturating_sub(4) + 1);
+
+        TransformError::InvalidArgument(match position {
+            Some(position) => format!(
+                "Invalid pattern '{}': {} at character {}",
+                pattern, reason, position
+            ),
+            None => format!("Invalid pattern '{}': {}", pattern, reason),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(spec: &str) -> Args {
+        Args::parse(spec).unwrap().0
+    }
+
+    #[test]
+    fn test_replace_expands_capture_groups() {
+        let swap = args(r#"("(?<user>\w+)@(\w+)", "$2 at ${user}")"#);
+        assert_eq!(
+            Replace.run_with("jo@home, al@work", &swap).unwrap(),
+            "home at jo, work at al"
+        );
+
+        let first = args(r#"("\d", "_", limit=1)"#);
+        assert_eq!(Replace.run_with("a1b2", &first).unwrap(), "a_b2");
+    }
+
+    #[test]
+    fn test_extract_matches_and_groups() {
+        let text = "id=7, id=42";
+
+        assert_eq!(
+            Extract.run_with(text, &args(r"('id=(\d+)')")).unwrap(),
+            "id=7\nid=42"
+        );
+        assert_eq!(
+            Extract
+                .run_with(text, &args(r"('id=(?<n>\d+)', n)"))
+                .unwrap(),
+            "7\n42"
+        );
+        assert_eq!(
+            Extract
+                .run_with(text, &args(r"('id=(\d+)', 2)"))
+                .unwrap_err()
+                .to_string(),
+            "Pattern has no group 2"
+        );
+    }
+
+    #[test]
+    fn test_split_with_limit() {
+        assert_eq!(
+            Split.run_with("a, b,c", &args(r#"(",\s*")"#)).unwrap(),
+            "a\nb\nc"
+        );
+        assert_eq!(
+            Split
+                .run_with("a, b,c", &args(r#"(",\s*", limit=2)"#))
+                .unwrap(),
+            "a\nb,c"
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern_and_missing_arguments() {
+        assert_eq!(
+            Split
+                .run_with("x", &args(r"('a{2,1}')"))
+                .unwrap_err()
+                .to_string(),
+            "Invalid pattern 'a{2,1}': invalid repetition count range, \
+             the start must be <= the end at character 2"
+        );
+        assert_eq!(
+            Replace.run("x").unwrap_err().to_string(),
+            "Missing argument: pattern"
+        );
+    }
+}
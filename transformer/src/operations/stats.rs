@@ -0,0 +1,196 @@
+use crate::args::Args;
+use crate::error::TransformError;
+use crate::operation::Operation;
+use crate::table::{Table, TableStyle};
+use serde::Serialize;
+use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub struct Stats;
+
+impl Operation for Stats {
+    fn name(&self) -> &'static str {
+        "stats"
+    }
+
+    fn description(&self) -> &'static str {
+        "Counts characters, words, lines and sentences and lists the most common words"
+    }
+
+    fn usage(&self) -> &'static str {
+        "stats([top=<n>, format=table|json, border=unicode|ascii, bold=true, max-width=<n>]) <text | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        self.run_with(input, &Args::default())
+    }
+
+    fn named_args(&self) -> &'static [&'static str] {
+        &["top", "format", "border", "bold", "max-width"]
+    }
+
+    fn run_with(&self, input: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 0, self.named_args())?;
+        let top = args.named_usize("top")?.unwrap_or(5);
+        let stats = TextStats::new(input, top);
+
+        match args.named("format") {
+            None | Some("table") => Ok(stats.render(&TableStyle::from_args(args)?)),
+            Some("json") => Ok(serde_json::to_string_pretty(&stats)?),
+            Some(other) => Err(TransformError::InvalidArgument(format!(
+                "Unknown format: {} (expected table or json)",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TextStats {
+    characters: usize,
+    bytes: usize,
+    graphemes: usize,
+    words: usize,
+    lines: usize,
+    sentences: usize,
+    average_word_length: f64,
+    top_words: Vec<WordCount>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct WordCount {
+    word: String,
+    count: usize,
+}
+
+impl TextStats {
+    /// Collects statistics for `text`, keeping the `top` most frequent words.
+    /// Words are compared case-insensitively; ties are broken alphabetically.
+    /// The average word length is measured in characters.
+    pub fn new(text: &str, top: usize) -> TextStats {
+        let words: Vec<&str> = text.unicode_words().collect();
+
+        let mut frequencies: HashMap<String, usize> = HashMap::new();
+        for word in &words {
+            *frequencies.entry(word.to_lowercase()).or_default() += 1;
+        }
+        let mut top_words: Vec<WordCount> = frequencies
+            .into_iter()
+            .map(|(word, count)| WordCount { word, count })
+            .collect();
+        top_words.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+        top_words.truncate(top);
+
+        let word_chars: usize = words.iter().map(|word| word.chars().count()).sum();
+        let average_word_length = match words.len() {
+            0 => 0.0,
+            count => (word_chars as f64 / count as f64 * 100.0).round() / 100.0,
+        };
+
+        TextStats {
+            characters: text.chars().count(),
+            bytes: text.len(),
+            graphemes: text.graphemes(true).count(),
+            words: words.len(),
+            lines: text.lines().count(),
+            sentences: count_sentences(text),
+            average_word_length,
+            top_words,
+        }
+    }
+
+    /// Renders the counts and the top words as two tables.
+    pub fn render(&self, style: &TableStyle) -> String {
+        let mut counts = Table::new(["Metric", "Value"]);
+        for (metric, value) in [
+            ("Characters", self.characters.to_string()),
+            ("Bytes", self.bytes.to_string()),
+            ("Graphemes", self.graphemes.to_string()),
+            ("Words", self.words.to_string()),
+            ("Lines", self.lines.to_string()),
+            ("Sentences", self.sentences.to_string()),
+            (
+                "Average word length",
+                format!("{:.2}", self.average_word_length),
+            ),
+        ] {
+            counts.push_row([metric.to_string(), value]);
+        }
+
+        if self.top_words.is_empty() {
+            return counts.render(style);
+        }
+
+        let mut words = Table::new(["Word", "Count"]);
+        for word in &self.top_words {
+            words.push_row([word.word.clone(), word.count.to_string()]);
+        }
+        format!("{}\n{}", counts.render(style), words.render(style))
+    }
+}
+
+fn count_sentences(text: &str) -> usize {
+    // unicode-segmentation panics when splitting an empty string into
+    // sentences.
+    if text.is_empty() {
+        return 0;
+    }
+    text.unicode_sentences()
+        .filter(|sentence| !sentence.trim().is_empty())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts() {
+        let stats = TextStats::new("Příliš žluťoučký kůň. Kůň úpěl!\nÓdy.", 2);
+
+        assert_eq!(stats.characters, 36);
+        assert_eq!(stats.bytes, 50);
+        assert_eq!(stats.graphemes, 36);
+        assert_eq!(stats.words, 6);
+        assert_eq!(stats.lines, 2);
+        assert_eq!(stats.sentences, 3);
+        assert_eq!(stats.average_word_length, 4.67);
+        assert_eq!(
+            stats.top_words,
+            [
+                WordCount {
+                    word: "kůň".to_string(),
+                    count: 2
+                },
+                WordCount {
+                    word: "příliš".to_string(),
+                    count: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_text() {
+        let stats = TextStats::new("", 5);
+        assert_eq!(TextStats::new(" \n", 5).sentences, 0);
+
+        assert_eq!(stats.words, 0);
+        assert_eq!(stats.sentences, 0);
+        assert_eq!(stats.average_word_length, 0.0);
+        assert!(stats.top_words.is_empty());
+    }
+
+    #[test]
+    fn test_json_format() {
+        let (args, _) = Args::parse("(top=1, format=json)").unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&Stats.run_with("a b a", &args).unwrap()).unwrap();
+
+        assert_eq!(json["words"], 3);
+        assert_eq!(
+            json["top_words"],
+            serde_json::json!([{"word": "a", "count": 2}])
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use crate::error::TransformError;
+use crate::operation::Operation;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub struct NoSpaces;
+
+impl Operation for NoSpaces {
+    fn name(&self) -> &'static str {
+        "no-spaces"
+    }
+
+    fn description(&self) -> &'static str {
+        "Removes all spaces from the text"
+    }
+
+    fn usage(&self) -> &'static str {
+        "no-spaces <text | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        Ok(input.replace(" ", ""))
+    }
+}
+
+pub struct Slugify;
+
+impl Operation for Slugify {
+    fn name(&self) -> &'static str {
+        "slugify"
+    }
+
+    fn description(&self) -> &'static str {
+        "Turns the text into a URL-friendly slug"
+    }
+
+    fn usage(&self) -> &'static str {
+        "slugify <text | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        Ok(slug::slugify(input))
+    }
+}
+
+pub struct Reverse;
+
+impl Operation for Reverse {
+    fn name(&self) -> &'static str {
+        "reverse"
+    }
+
+    fn description(&self) -> &'static str {
+        "Reverses the order of characters (grapheme clusters)"
+    }
+
+    fn usage(&self) -> &'static str {
+        "reverse <text | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        Ok(input.graphemes(true).rev().collect())
+    }
+}
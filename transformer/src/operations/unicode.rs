@@ -0,0 +1,117 @@
+use crate::error::TransformError;
+use crate::operation::Operation;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Rewrites the text character by character without changing its meaning,
+/// e.g. a normalization form or transliteration.
+pub struct Normalization {
+    name: &'static str,
+    description: &'static str,
+    usage: &'static str,
+    normalize: fn(&str) -> String,
+}
+
+impl Operation for Normalization {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn usage(&self) -> &'static str {
+        self.usage
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        Ok((self.normalize)(input))
+    }
+}
+
+pub fn operations() -> Vec<Box<dyn Operation>> {
+    vec![
+        Box::new(Normalization {
+            name: "nfc",
+            description: "Normalizes the text to NFC (composed characters)",
+            usage: "nfc <text | @path>",
+            normalize: |input| input.nfc().collect(),
+        }),
+        Box::new(Normalization {
+            name: "nfd",
+            description: "Normalizes the text to NFD (base characters followed by combining marks)",
+            usage: "nfd <text | @path>",
+            normalize: |input| input.nfd().collect(),
+        }),
+        Box::new(Normalization {
+            name: "nfkc",
+            description:
+                "Normalizes the text to NFKC, also folding compatibility characters like 'ﬁ'",
+            usage: "nfkc <text | @path>",
+            normalize: |input| input.nfkc().collect(),
+        }),
+        Box::new(Normalization {
+            name: "nfkd",
+            description: "Normalizes the text to NFKD",
+            usage: "nfkd <text | @path>",
+            normalize: |input| input.nfkd().collect(),
+        }),
+        Box::new(Normalization {
+            name: "strip-diacritics",
+            description: "Removes accents and other combining marks, keeping everything else",
+            usage: "strip-diacritics <text | @path>",
+            normalize: strip_diacritics,
+        }),
+        Box::new(Normalization {
+            name: "ascii",
+            description: "Transliterates the text to plain ASCII",
+            usage: "ascii <text | @path>",
+            normalize: to_ascii,
+        }),
+    ]
+}
+
+/// Decomposes the text so accents become separate combining marks, drops the
+/// marks and composes what is left again.
+pub fn strip_diacritics(input: &str) -> String {
+    input
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .nfc()
+        .collect()
+}
+
+/// Composes the text first so that base letters and combining marks are
+/// transliterated together, e.g. `e` + U+0301 becomes `e` rather than `e'`.
+pub fn to_ascii(input: &str) -> String {
+    deunicode::deunicode(&input.nfc().collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalization_forms() {
+        let composed = "\u{e9}\u{fb01}";
+        let decomposed: String = composed.nfd().collect();
+
+        assert_eq!(decomposed, "e\u{301}\u{fb01}");
+        assert_eq!(decomposed.nfc().collect::<String>(), composed);
+        assert_eq!(composed.nfkc().collect::<String>(), "\u{e9}fi");
+        assert_eq!(composed.nfkd().collect::<String>(), "e\u{301}fi");
+    }
+
+    #[test]
+    fn test_strip_diacritics_handles_combining_marks() {
+        assert_eq!(strip_diacritics("Příliš žluťoučký"), "Prilis zlutoucky");
+        assert_eq!(strip_diacritics("Cafe\u{301} ø"), "Cafe ø");
+    }
+
+    #[test]
+    fn test_ascii_transliteration() {
+        assert_eq!(to_ascii("Příliš žluťoučký"), "Prilis zlutoucky");
+        assert_eq!(to_ascii("Cafe\u{301} ø Łódź"), "Cafe o Lodz");
+    }
+}
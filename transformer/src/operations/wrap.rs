@@ -0,0 +1,254 @@
+use crate::args::Args;
+use crate::error::TransformError;
+use crate::operation::Operation;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// The widest line `wrap` produces. Padding is allocated up to the width,
+/// so an unbounded width would let a single argument exhaust memory.
+const MAX_WIDTH: usize = 10_000;
+
+pub struct Wrap;
+
+impl Operation for Wrap {
+    fn name(&self) -> &'static str {
+        "wrap"
+    }
+
+    fn description(&self) -> &'static str {
+        "Wraps the text to a width, breaking between words"
+    }
+
+    fn usage(&self) -> &'static str {
+        "wrap([width=<n>, indent=<n>, align=left|right|center|justify]) <text | @path>"
+    }
+
+    fn run(&self, input: &str) -> Result<String, TransformError> {
+        self.run_with(input, &Args::default())
+    }
+
+    fn named_args(&self) -> &'static [&'static str] {
+        &["width", "indent", "align"]
+    }
+
+    fn run_with(&self, input: &str, args: &Args) -> Result<String, TransformError> {
+        args.expect(self.name(), 0, self.named_args())?;
+        let width = args.named_usize("width")?.unwrap_or(80);
+        let indent = args.named_usize("indent")?.unwrap_or(0);
+        let align = match args.named("align") {
+            None | Some("left") => Align::Left,
+            Some("right") => Align::Right,
+            Some("center") => Align::Center,
+            Some("justify") => Align::Justify,
+            Some(other) => {
+                return Err(TransformError::InvalidArgument(format!(
+                    "Unknown alignment: {} (expected left, right, center or justify)",
+                    other
+                )))
+            }
+        };
+        for (name, value) in [("width", width), ("indent", indent)] {
+            if value > MAX_WIDTH {
+                return Err(TransformError::InvalidArgument(format!(
+                    "The {} of {} is larger than the maximum of {}",
+                    name, value, MAX_WIDTH
+                )));
+            }
+        }
+        if width <= indent {
+            return Err(TransformError::InvalidArgument(format!(
+                "Width {} leaves no room after an indent of {}",
+                width, indent
+            )));
+        }
+
+        Ok(wrap(input, width, indent, align))
+    }
+
+    /// Two neighbouring lines of a paragraph are together wider than a line,
+    /// and no text is wider than its length in bytes, so a paragraph has at
+    /// most `2 * len / available + 1` lines. Each adds up to `width` columns
+    /// of indent and padding and a line break.
+    fn output_bound(&self, input: &str, args: &Args) -> Option<usize> {
+        let width = args.named_usize("width").ok()?.unwrap_or(80);
+        let indent = args.named_usize("indent").ok()?.unwrap_or(0);
+        let available = width
+            .checked_sub(indent)
+            .filter(|&available| available > 0)?;
+        let lines = 2 * input.len() / available + input.lines().count() + 1;
+
+        Some(input.len().saturating_add(lines.saturating_mul(width + 1)))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+    /// Stretches every line but the last of a paragraph to the full width.
+    Justify,
+}
+
+/// Wraps every line of `text` as its own paragraph so that no line is wider
+/// than `width` columns, `indent` included. Widths are measured in terminal
+/// columns, so wide characters such as CJK count twice. Words wider than a
+/// line are split between graphemes.
+pub fn wrap(text: &str, width: usize, indent: usize, align: Align) -> String {
+    let available = width - indent;
+
+    let mut output = Vec::new();
+    for paragraph in text.lines() {
+        let lines = fill(paragraph, available);
+        if lines.is_empty() {
+            output.push(String::new());
+            continue;
+        }
+
+        let last = lines.len() - 1;
+        for (index, words) in lines.iter().enumerate() {
+            let line = match align {
+                Align::Justify if index < last => justify(words, available),
+                _ => words.join(" "),
+            };
+            let padding = match align {
+                Align::Left | Align::Justify => 0,
+                Align::Right => available.saturating_sub(line.width()),
+                Align::Center => available.saturating_sub(line.width()) / 2,
+            };
+            output.push(format!("{}{}", " ".repeat(indent + padding), line));
+        }
+    }
+
+    output.join("\n")
+}
+
+/// Greedily packs the words of `paragraph` into lines of at most `width`
+/// columns.
+fn fill(paragraph: &str, width: usize) -> Vec<Vec<&str>> {
+    let mut lines: Vec<Vec<&str>> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut current_width = 0;
+
+    for word in paragraph
+        .split_whitespace()
+        .flat_map(|word| split_word(word, width))
+    {
+        let word_width = word.width();
+        if !current.is_empty() && current_width + 1 + word_width > width {
+            lines.push(std::mem::take(&mut current));
+            current_width = 0;
+        }
+        if !current.is_empty() {
+            current_width += 1;
+        }
+        current.push(word);
+        current_width += word_width;
+    }
+
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Splits a word that is wider than `width` into pieces that fit.
+fn split_word(word: &str, width: usize) -> Vec<&str> {
+    if word.width() <= width {
+        return vec![word];
+    }
+
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut piece_width = 0;
+    for (offset, grapheme) in word.grapheme_indices(true) {
+        let grapheme_width = grapheme.width();
+        if piece_width + grapheme_width > width && offset > start {
+            pieces.push(&word[start..offset]);
+            start = offset;
+            piece_width = 0;
+        }
+        piece_width += grapheme_width;
+    }
+    pieces.push(&word[start..]);
+    pieces
+}
+
+/// Joins `words` with spaces spread as evenly as possible to reach `width`;
+/// the leftmost gaps get the extra spaces.
+fn justify(words: &[&str], width: usize) -> String {
+    if words.len() < 2 {
+        return words.join(" ");
+    }
+
+    let gaps = words.len() - 1;
+    let spaces = width.saturating_sub(words.iter().map(|word| word.width()).sum());
+    let mut line = String::new();
+    for (index, word) in words.iter().enumerate() {
+        line.push_str(word);
+        if index < gaps {
+            let extra = usize::from(index < spaces % gaps);
+            line.push_str(&" ".repeat(spaces / gaps + extra));
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "The quick brown fox jumps over the lazy dog";
+
+    #[test]
+    fn test_wrap_left_with_indent() {
+        assert_eq!(
+            wrap(TEXT, 16, 2, Align::Left),
+            "  The quick\n  brown fox\n  jumps over the\n  lazy dog"
+        );
+    }
+
+    #[test]
+    fn test_wrap_right_and_center() {
+        assert_eq!(wrap("ab cd ef", 5, 0, Align::Right), "ab cd\n   ef");
+        assert_eq!(wrap("ab cd ef", 6, 0, Align::Center), "ab cd\n  ef");
+    }
+
+    #[test]
+    fn test_justify_spreads_spaces_and_keeps_last_line() {
+        assert_eq!(
+            wrap(TEXT, 16, 0, Align::Justify),
+            "The  quick brown\nfox  jumps  over\nthe lazy dog"
+        );
+    }
+
+    #[test]
+    fn test_wide_characters_and_long_words() {
+        // Each of these characters takes two columns.
+        assert_eq!(
+            wrap("日本語 テキスト", 8, 0, Align::Left),
+            "日本語\nテキスト"
+        );
+        assert_eq!(wrap("abcdefgh", 3, 0, Align::Left), "abc\ndef\ngh");
+    }
+
+    #[test]
+    fn test_width_and_indent_are_capped() {
+        let (args, _) = Args::parse("(width=100000000000, align=right)").unwrap();
+        assert_eq!(
+            Wrap.run_with("ab", &args).unwrap_err().to_string(),
+            "The width of 100000000000 is larger than the maximum of 10000"
+        );
+
+        let (args, _) = Args::parse("(width=10000, indent=20000)").unwrap();
+        assert!(Wrap.run_with("ab", &args).is_err());
+
+        let (args, _) = Args::parse("(width=10000, indent=9998)").unwrap();
+        assert_eq!(Wrap.run_with("ab", &args).unwrap().len(), 10_000);
+    }
+
+    #[test]
+    fn test_paragraphs_are_wrapped_separately() {
+        assert_eq!(wrap("a b\n\nc", 10, 0, Align::Left), "a b\n\nc");
+    }
+}
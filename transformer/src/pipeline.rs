@@ -0,0 +1,462 @@
+use crate::args::Args;
+use crate::config::Config;
+use crate::error::TransformError;
+use crate::operation::{Operation, Registry};
+use crate::source::Source;
+use crate::validation::ValidationPolicy;
+use std::fmt;
+
+/// A chain of operations where each stage's output feeds the next one,
+/// written as `lowercase | no-spaces | binary`.
+#[derive(Debug, PartialEq)]
+pub struct Pipeline {
+    stages: Vec<Stage>,
+}
+
+/// One operation in a pipeline, with the arguments written in parentheses
+/// directly after its name, e.g. `split(", ")`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stage {
+    pub name: String,
+    pub args: Args,
+}
+
+impl Pipeline {
+    /// Splits a command line into its pipeline and the text it operates on.
+    ///
+    /// `lowercase | no-spaces Hello World` yields the stages `lowercase` and
+    /// `no-spaces` and the text `Hello World`. Arguments must follow the
+    /// operation name without a space: `replace("\s+", "-") a  b`. Runs of
+    /// whitespace in the text collapse to one space unless it is quoted.
+    pub fn parse_line(line: &str) -> Result<(Pipeline, String), String> {
+        let mut stages = Vec::new();
+        let mut rest = line.trim_start();
+
+        loop {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == '|' || c == '(')
+                .unwrap_or(rest.len());
+            if end == 0 {
+                return Err("Missing operation name in pipeline".to_string());
+            }
+            let name = rest[..end].to_string();
+            rest = &rest[end..];
+
+            let args = match rest.starts_with('(') {
+                true => {
+                    let (args, after) = Args::parse(rest)?;
+                    rest = after;
+                    args
+                }
+                false => Args::default(),
+            };
+            stages.push(Stage { name, args });
+            rest = rest.trim_start();
+
+            match rest.strip_prefix('|') {
+                Some(next) => rest = next.trim_start(),
+                None => break,
+            }
+        }
+
+        // Quoted literals keep their spacing; see `Source::parse`.
+        let text = match rest.starts_with('\'') {
+            true => rest.trim_end().to_string(),
+            false => rest.split_whitespace().collect::<Vec<&str>>().join(" "),
+        };
+        Ok((Pipeline { stages }, text))
+    }
+
+    /// Parses a pipeline given on its own, without any text after it.
+    pub fn parse(spec: &str) -> Result<Pipeline, String> {
+        match Pipeline::parse_line(spec)? {
+            (pipeline, text) if text.is_empty() => Ok(pipeline),
+            (_, text) => Err(format!("Unexpected text after pipeline: {}", text)),
+        }
+    }
+
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    /// Expands the aliases of `config` and fills in the default arguments
+    /// of every operation, then the output defaults of the last one.
+    ///
+    /// As in a shell, an alias is not expanded again inside its own
+    /// definition, so `lowercase = "trim | lowercase"` refers to the
+    /// operation.
+    pub fn configure(&self, config: &Config) -> Result<Pipeline, String> {
+        let mut stages = Vec::new();
+        expand(&self.stages, config, &mut Vec::new(), &mut stages)?;
+
+        for stage in &mut stages {
+            if let Some(defaults) = config.defaults.get(&stage.name) {
+                for (key, value) in defaults {
+                    stage.args.insert_default(key, value);
+                }
+            }
+        }
+        if let Some(stage) = stages.last_mut() {
+            if let Some(operation) = crate::registry().get(&stage.name) {
+                for (key, value) in &config.output_defaults {
+                    if operation.named_args().contains(&key.as_str()) {
+                        stage.args.insert_default(key, value);
+                    }
+                }
+            }
+        }
+        Ok(Pipeline { stages })
+    }
+
+    /// Runs every stage in order and reports which stage failed on error.
+    ///
+    /// `input` is resolved through [`Source::parse`] for the first stage and
+    /// checked against `policy` with that stage's limits. Only text given by
+    /// the user is validated: files and stdin are read whole, and the output
+    /// of a stage is passed on as it is. The output of every stage must fit
+    /// in `policy.max_output`.
+    pub fn run(
+        &self,
+        registry: &Registry,
+        policy: &ValidationPolicy,
+        input: &str,
+    ) -> Result<String, TransformError> {
+        self.execute(registry, policy, input, true)
+    }
+
+    /// Like [`Pipeline::run`], but `text` is always taken literally, so
+    /// input from other programs can never name a file or stdin.
+    pub fn run_text(
+        &self,
+        registry: &Registry,
+        policy: &ValidationPolicy,
+        text: &str,
+    ) -> Result<String, TransformError> {
+        self.execute(registry, policy, text, false)
+    }
+
+    fn execute(
+        &self,
+        registry: &Registry,
+        policy: &ValidationPolicy,
+        input: &str,
+        resolve_source: bool,
+    ) -> Result<String, TransformError> {
+        let mut output = String::new();
+
+        for (index, stage) in self.stages.iter().enumerate() {
+            let run = |operation: &dyn Operation, text: &str| {
+                if policy.max_output > 0 {
+                    if let Some(bound) = operation.output_bound(text, &stage.args) {
+                        policy.validate_output(bound)?;
+                    }
+                }
+                operation.run_with(text, &stage.args)
+            };
+            let result = registry.find(&stage.name).and_then(|operation| {
+                let source = match index {
+                    0 if resolve_source => Source::parse(input),
+                    0 => Source::Literal(input.to_string()),
+                    _ => return run(operation, &std::mem::take(&mut output)),
+                };
+                match source {
+                    Source::Literal(text) => {
+                        policy.validate_input(operation.name(), &text)?;
+                        run(operation, &text)
+                    }
+                    source => operation.run_source(source, &stage.args),
+                }
+            });
+            let result = result.and_then(|output| {
+                policy.validate_output(output.len())?;
+                Ok(output)
+            });
+
+            output = match result {
+                Ok(output) => output,
+                Err(err) if self.stages.len() == 1 => return Err(err),
+                Err(err) => {
+                    return Err(TransformError::Stage {
+                        index: index + 1,
+                        operation: stage.name.clone(),
+                        source: Box::new(err),
+                    })
+                }
+            };
+        }
+
+        Ok(output)
+    }
+}
+
+/// Appends `stages` to `output`, replacing aliases that are not already
+/// being expanded with their stages.
+fn expand<'a>(
+    stages: &[Stage],
+    config: &'a Config,
+    expanding: &mut Vec<&'a str>,
+    output: &mut Vec<Stage>,
+) -> Result<(), String> {
+    for stage in stages {
+        match config.aliases.get_key_value(&stage.name) {
+            Some((name, spec)) if !expanding.contains(&name.as_str()) => {
+                if !stage.args.is_empty() {
+                    return Err(format!("Alias {} takes no arguments", name));
+                }
+                let alias = Pipeline::parse(spec)
+                    .map_err(|e| format!("Invalid alias '{}': {}", name, e))?;
+
+                expanding.push(name);
+                expand(&alias.stages, config, expanding, output)?;
+                expanding.pop();
+            }
+            _ => output.push(stage.clone()),
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, stage) in self.stages.iter().enumerate() {
+            if index > 0 {
+                write!(f, " | ")?;
+            }
+            write!(f, "{}", stage.name)?;
+            if !stage.args.is_empty() {
+                write!(f, "{}", stage.args)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::default_registry;
+
+    fn names(pipeline: &Pipeline) -> Vec<&str> {
+        pipeline
+            .stages()
+            .iter()
+            .map(|stage| stage.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_single_operation() {
+        let (pipeline, text) = Pipeline::parse_line("lowercase  Hello   World\n").unwrap();
+
+        assert_eq!(names(&pipeline), ["lowercase"]);
+        assert_eq!(text, "Hello World");
+    }
+
+    #[test]
+    fn test_parse_pipeline_with_and_without_spaces() {
+        let (spaced, _) = Pipeline::parse_line("lowercase | no-spaces | binary Hi").unwrap();
+        let (compact, text) = Pipeline::parse_line("lowercase|no-spaces|binary Hi").unwrap();
+
+        assert_eq!(spaced, compact);
+        assert_eq!(names(&compact), ["lowercase", "no-spaces", "binary"]);
+        assert_eq!(text, "Hi");
+    }
+
+    #[test]
+    fn test_parse_stage_arguments() {
+        let (pipeline, text) =
+            Pipeline::parse_line(r#"replace("\s*,\s*", "; ")|uppercase a ,  b"#).unwrap();
+
+        assert_eq!(names(&pipeline), ["replace", "uppercase"]);
+        assert_eq!(pipeline.stages()[0].args.get(0), Some(r"\s*,\s*"));
+        assert_eq!(pipeline.stages()[0].args.get(1), Some("; "));
+        assert!(pipeline.stages()[1].args.is_empty());
+        assert_eq!(text, "a , b");
+        assert_eq!(
+            pipeline.to_string(),
+            r#"replace("\s*,\s*", "; ") | uppercase"#
+        );
+    }
+
+    #[test]
+    fn test_run_rejects_arguments_for_plain_operations() {
+        let registry = default_registry();
+        let (pipeline, text) = Pipeline::parse_line("lowercase(x) Hi").unwrap();
+        let err = pipeline
+            .run(&registry, &ValidationPolicy::default(), &text)
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "lowercase takes no arguments");
+    }
+
+    #[test]
+    fn test_quoted_text_keeps_spacing() {
+        let registry = default_registry();
+        let (pipeline, text) = Pipeline::parse_line("uppercase 'a  b '  ").unwrap();
+
+        assert_eq!(text, "'a  b '");
+        assert_eq!(
+            pipeline
+                .run(&registry, &ValidationPolicy::default(), &text)
+                .unwrap(),
+            "A  B "
+        );
+    }
+
+    #[test]
+    fn test_bare_input_is_text_for_every_operation() {
+        let registry = default_registry();
+        let pipeline = Pipeline::parse("csv-to-json | json-minify").unwrap();
+
+        assert_eq!(
+            pipeline
+                .run(&registry, &ValidationPolicy::default(), "Name,Age\nJo,32")
+                .unwrap(),
+            r#"[{"Name":"Jo","Age":32}]"#
+        );
+    }
+
+    #[test]
+    fn test_run_text_never_reads_files() {
+        let registry = default_registry();
+        let pipeline = Pipeline::parse("uppercase").unwrap();
+
+        assert_eq!(
+            pipeline
+                .run_text(&registry, &ValidationPolicy::default(), "@notes.txt")
+                .unwrap(),
+            "@NOTES.TXT"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_empty_stage() {
+        assert!(Pipeline::parse_line("lowercase | | binary Hi").is_err());
+        assert!(Pipeline::parse_line("lowercase |").is_err());
+    }
+
+    #[test]
+    fn test_run_feeds_output_into_next_stage() {
+        let registry = default_registry();
+        let (pipeline, text) =
+            Pipeline::parse_line("uppercase | reverse | no-spaces ab c").unwrap();
+
+        assert_eq!(
+            pipeline
+                .run(&registry, &ValidationPolicy::default(), &text)
+                .unwrap(),
+            "CBA"
+        );
+    }
+
+    #[test]
+    fn test_run_validates_only_the_input() {
+        let registry = default_registry();
+        let text = "a".repeat(60);
+        let pipeline = Pipeline::parse("hex | from-hex").unwrap();
+
+        assert_eq!(
+            pipeline
+                .run(&registry, &ValidationPolicy::default(), &text)
+                .unwrap(),
+            text
+        );
+    }
+
+    #[test]
+    fn test_run_refuses_outputs_above_max_output() {
+        let registry = default_registry();
+        let policy = ValidationPolicy {
+            max_length: 0,
+            max_output: 1000,
+            ..ValidationPolicy::default()
+        };
+        let run = |spec: &str, input: &str| {
+            Pipeline::parse(spec)
+                .unwrap()
+                .run_text(&registry, &policy, input)
+        };
+
+        let err = run("replace('', '0123456789')", &"a".repeat(200)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Output is larger than the maximum of 1000 bytes"
+        );
+        assert_eq!(err.code(), "E009");
+        assert!(run("wrap(width=100, align=right)", &"a\n".repeat(50)).is_err());
+
+        let text = "a".repeat(100);
+        assert_eq!(run("hex | hex | hex", &text).unwrap().len(), 800);
+        assert_eq!(
+            run("hex | hex | hex | hex", &text).unwrap_err().to_string(),
+            "Stage 4 (hex) failed: Output is larger than the maximum of 1000 bytes"
+        );
+    }
+
+    #[test]
+    fn test_output_bounds_cover_the_output() {
+        let registry = default_registry();
+
+        for (spec, input) in [
+            ("replace('(\\w+)', '<$1$1>')", "ab cd ef"),
+            ("replace('', '--', limit=2)", "abc"),
+            (
+                "wrap(width=10, align=justify)",
+                "The quick brown fox jumps over the lazy dog\n\nok",
+            ),
+            (
+                "wrap(width=6, indent=2, align=right)",
+                "日本語 テキスト abcdefghijk",
+            ),
+            ("csv(bold=true)", "a,b\n1,x\n22,yyyy"),
+            (
+                "csv-query('select b, a, b where a > 1', border=ascii)",
+                "a,b\n1,x\n22,yyyy",
+            ),
+            ("csv-group('by b of a')", "a,b\n1,x\n22,x"),
+            ("csv-to-json", "Name,Age\n\"Jo \"\"J\"\"\",32\n\"A\u{1}B\","),
+            ("json-to-csv", r#"[{"a":"x,\"y"},{"b":{"c":1}}]"#),
+            ("csv-to-markdown", "a,b|c\n1,\"x\ny\"\n"),
+            ("json-pretty", r#"{"a":[1,[2,[3,{"b":null}]]]}"#),
+            ("json-query('$..a')", r#"{"a":{"a":{"a":1}}}"#),
+        ] {
+            let pipeline = Pipeline::parse(spec).unwrap();
+            let stage = &pipeline.stages()[0];
+            let operation = registry.find(&stage.name).unwrap();
+
+            let output = operation.run_with(input, &stage.args).unwrap();
+            let bound = operation.output_bound(input, &stage.args).unwrap();
+            assert!(
+                bound >= output.len(),
+                "{}: {} < {}",
+                spec,
+                bound,
+                output.len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_run_reports_failing_stage() {
+        let registry = default_registry();
+        let (pipeline, text) = Pipeline::parse_line("lowercase | no-spaces | binary  ").unwrap();
+        let err = pipeline
+            .run(&registry, &ValidationPolicy::default(), &text)
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Stage 1 (lowercase) failed: String is empty"
+        );
+
+        let (pipeline, text) = Pipeline::parse_line("lowercase | slugfy Hi").unwrap();
+        let err = pipeline
+            .run(&registry, &ValidationPolicy::default(), &text)
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Stage 2 (slugfy) failed: Invalid operation: slugfy (did you mean 'slugify'?)"
+        );
+    }
+}
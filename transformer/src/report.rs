@@ -0,0 +1,174 @@
+use crate::error::{TransformError, Violation};
+use serde::Serialize;
+use std::time::Duration;
+
+/// How results are written: plain text for people, or one JSON object per
+/// command for scripts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl Format {
+    pub fn parse(value: &str) -> Result<Format, String> {
+        match value {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown format: {} (expected text or json)", value)),
+        }
+    }
+}
+
+/// A command that has been run, with its result and how long it took.
+pub struct Outcome {
+    pub operation: String,
+    pub input: String,
+    pub result: Result<String, TransformError>,
+    pub elapsed: Duration,
+}
+
+impl Outcome {
+    /// Renders the outcome as a single-line JSON object. `id` identifies the
+    /// command when there is more than one, e.g. a job id or line number.
+    pub fn to_json(&self, id: Option<usize>) -> String {
+        let report = Report {
+            id,
+            operation: &self.operation,
+            input: &self.input,
+            output: self.result.as_deref().ok(),
+            error: self.result.as_ref().err().map(ErrorReport::new),
+            elapsed_ms: (self.elapsed.as_secs_f64() * 1e6).round() / 1e3,
+        };
+        serde_json::to_string(&report).expect("a report always serializes")
+    }
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    operation: &'a str,
+    input: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorReport<'a>>,
+    elapsed_ms: f64,
+}
+
+/// The machine-readable part of a [`TransformError`]. Location fields are
+/// only present for the error kinds that have them.
+#[derive(Serialize)]
+struct ErrorReport<'a> {
+    kind: &'static str,
+    code: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stage: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stage_operation: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestion: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    violations: Option<&'a [Violation]>,
+}
+
+impl<'a> ErrorReport<'a> {
+    fn new(err: &'a TransformError) -> ErrorReport<'a> {
+        let mut report = ErrorReport {
+            kind: err.kind(),
+            code: err.code(),
+            message: err.to_string(),
+            stage: None,
+            stage_operation: None,
+            suggestion: None,
+            line: None,
+            column: None,
+            field: None,
+            offset: None,
+            violations: None,
+        };
+
+        if let TransformError::Stage {
+            index, operation, ..
+        } = err
+        {
+            report.stage = Some(*index);
+            report.stage_operation = Some(operation);
+        }
+
+        match err.root() {
+            TransformError::UnknownOperation { suggestion, .. } => {
+                report.suggestion = suggestion.as_deref()
+            }
+            TransformError::Csv { line, field, .. } => {
+                report.line = *line;
+                report.field = *field;
+            }
+            TransformError::Encoding { offset, .. } => report.offset = Some(*offset),
+            TransformError::Schema(violations) => report.violations = Some(violations),
+            TransformError::Json { line, column, .. } if *line > 0 => {
+                report.line = Some(*line as u64);
+                report.column = Some(*column as u64);
+            }
+            _ => {}
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(result: Result<String, TransformError>) -> Outcome {
+        Outcome {
+            operation: "lowercase | from-hex".to_string(),
+            input: "6869".to_string(),
+            result,
+            elapsed: Duration::from_micros(1500),
+        }
+    }
+
+    #[test]
+    fn test_json_report_with_output() {
+        let json = outcome(Ok("hi".to_string())).to_json(Some(3));
+
+        assert_eq!(
+            json,
+            r#"{"id":3,"operation":"lowercase | from-hex","input":"6869","output":"hi","elapsed_ms":1.5}"#
+        );
+    }
+
+    #[test]
+    fn test_json_report_with_structured_error() {
+        let err = TransformError::Stage {
+            index: 2,
+            operation: "from-hex".to_string(),
+            source: Box::new(TransformError::Encoding {
+                offset: 2,
+                message: "Invalid hex digit 'z'".to_string(),
+            }),
+        };
+        let json = outcome(Err(err)).to_json(None);
+
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"operation":"lowercase | from-hex","input":"6869","error":{"kind":"encoding","code":"E006","#,
+                r#""message":"Stage 2 (from-hex) failed: Invalid hex digit 'z' at byte offset 2","#,
+                r#""stage":2,"stage_operation":"from-hex","offset":2},"elapsed_ms":1.5}"#
+            )
+        );
+    }
+}
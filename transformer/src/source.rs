@@ -0,0 +1,165 @@
+use crate::error::TransformError;
+use std::fs::File;
+use std::io::{self, Cursor, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static STDIN_IN_USE: AtomicBool = AtomicBool::new(false);
+
+/// Marks stdin as taken, e.g. by the command loop, so that `-` can't read
+/// from it as well. Returns `false` if it already was taken.
+pub fn claim_stdin() -> bool {
+    !STDIN_IN_USE.swap(true, Ordering::SeqCst)
+}
+
+/// Where an operation's input comes from.
+#[derive(Debug, PartialEq)]
+pub enum Source {
+    Literal(String),
+    File(String),
+    Stdin,
+}
+
+impl Source {
+    /// Interprets an operation's input: `@path` names a file, `-` stands for
+    /// stdin and `'...'` is literal text, with `''` for a quote inside it.
+    /// Anything else is literal text, whatever the operation.
+    pub fn parse(input: &str) -> Source {
+        if let Some(path) = input.strip_prefix('@') {
+            return Source::File(path.to_string());
+        }
+        if input == "-" {
+            return Source::Stdin;
+        }
+        if let Some(text) = input
+            .strip_prefix('\'')
+            .and_then(|rest| rest.strip_suffix('\''))
+        {
+            return Source::Literal(text.replace("''", "'"));
+        }
+
+        Source::Literal(input.to_string())
+    }
+
+    /// Opens the source as a stream of raw bytes.
+    pub fn open(self) -> Result<Box<dyn Read>, TransformError> {
+        match self {
+            Source::Literal(text) => Ok(Box::new(Cursor::new(text.into_bytes()))),
+            Source::File(path) => Ok(Box::new(File::open(path)?)),
+            Source::Stdin if !claim_stdin() => Err(TransformError::InvalidArgument(
+                "Standard input is already in use; pass the text or a file instead".to_string(),
+            )),
+            Source::Stdin => Ok(Box::new(io::stdin().lock())),
+        }
+    }
+
+    /// Reads the whole source as text, see [`decode`].
+    pub fn read(self) -> Result<String, TransformError> {
+        if let Source::Literal(text) = self {
+            return Ok(text);
+        }
+
+        let mut bytes = Vec::new();
+        self.open()?.read_to_end(&mut bytes)?;
+        decode(bytes)
+    }
+}
+
+/// Turns bytes of unknown encoding into text. A byte order mark selects
+/// UTF-8 or UTF-16; without one the bytes are UTF-8 if they are valid and
+/// Latin-1 otherwise, so any input can be read.
+pub fn decode(bytes: Vec<u8>) -> Result<String, TransformError> {
+    match bytes.as_slice() {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => {
+            String::from_utf8(rest.to_vec()).map_err(|e| TransformError::Encoding {
+                offset: e.utf8_error().valid_up_to() + 3,
+                message: "Invalid UTF-8".to_string(),
+            })
+        }
+        [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, u16::from_be_bytes),
+        _ => String::from_utf8(bytes)
+            .or_else(|e| Ok(e.into_bytes().iter().map(|&byte| byte as char).collect())),
+    }
+}
+
+/// Decodes UTF-16 after its byte order mark; offsets count the mark too.
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> Result<String, TransformError> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(TransformError::Encoding {
+            offset: bytes.len() + 1,
+            message: "Incomplete UTF-16 code unit".to_string(),
+        });
+    }
+
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    let mut text = String::with_capacity(bytes.len() / 2);
+    let mut offset = 2;
+    for c in char::decode_utf16(units) {
+        match c {
+            Ok(c) => {
+                text.push(c);
+                offset += c.len_utf16() * 2;
+            }
+            Err(_) => {
+                return Err(TransformError::Encoding {
+                    offset,
+                    message: "Unpaired UTF-16 surrogate".to_string(),
+                })
+            }
+        }
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sources() {
+        assert_eq!(
+            Source::parse("@data.csv"),
+            Source::File("data.csv".to_string())
+        );
+        assert_eq!(Source::parse("-"), Source::Stdin);
+        assert_eq!(
+            Source::parse("'@it''s'"),
+            Source::Literal("@it's".to_string())
+        );
+        assert_eq!(
+            Source::parse("data.csv"),
+            Source::Literal("data.csv".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_detects_encoding() {
+        assert_eq!(decode("žluť".as_bytes().to_vec()).unwrap(), "žluť");
+        assert_eq!(decode(b"\xEF\xBB\xBFabc".to_vec()).unwrap(), "abc");
+        assert_eq!(decode(b"\xFF\xFEh\x00i\x00".to_vec()).unwrap(), "hi");
+        assert_eq!(decode(b"\xFE\xFF\x00h\x00i".to_vec()).unwrap(), "hi");
+        assert_eq!(decode(b"caf\xE9".to_vec()).unwrap(), "café");
+    }
+
+    #[test]
+    fn test_decode_reports_broken_utf16() {
+        let err = decode(b"\xFF\xFEh\x00\x00\xD8".to_vec()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unpaired UTF-16 surrogate at byte offset 4"
+        );
+
+        let err = decode(b"\xFF\xFEh".to_vec()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Incomplete UTF-16 code unit at byte offset 2"
+        );
+    }
+
+    #[test]
+    fn test_literal_sources_read_as_is() {
+        let source = Source::parse("'  spaced  '");
+
+        assert_eq!(source.read().unwrap(), "  spaced  ");
+    }
+}
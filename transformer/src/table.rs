@@ -0,0 +1,381 @@
+use crate::args::Args;
+use crate::error::TransformError;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Border {
+    /// Box-drawing characters (`┌─┬─┐`).
+    Unicode,
+    /// Plain `+-|` borders for terminals and files without Unicode support.
+    Ascii,
+}
+
+#[derive(Debug, Clone)]
+pub struct TableStyle {
+    pub border: Border,
+    /// Cells wider than this are truncated with an ellipsis; `0` means no
+    /// limit.
+    pub max_column_width: usize,
+    /// Renders the header row in bold using ANSI escape codes.
+    pub bold_headers: bool,
+}
+
+/// Box-drawing borders without any escape codes, so the output is the same
+/// wherever it ends up.
+impl Default for TableStyle {
+    fn default() -> Self {
+        Self {
+            border: Border::Unicode,
+            max_column_width: 32,
+            bold_headers: false,
+        }
+    }
+}
+
+impl TableStyle {
+    /// The style chosen by the `border=unicode|ascii`, `bold=true` and
+    /// `max-width=<n>` arguments of an operation that renders tables.
+    pub fn from_args(args: &Args) -> Result<TableStyle, TransformError> {
+        let border = match args.named("border") {
+            None | Some("unicode") => Border::Unicode,
+            Some("ascii") => Border::Ascii,
+            Some(other) => {
+                return Err(TransformError::InvalidArgument(format!(
+                    "Unknown border: {} (expected unicode or ascii)",
+                    other
+                )))
+            }
+        };
+
+        Ok(TableStyle {
+            border,
+            bold_headers: args.named_bool("bold")?,
+            max_column_width: args.named_usize("max-width")?.unwrap_or(32),
+        })
+    }
+}
+
+struct BorderChars {
+    horizontal: char,
+    vertical: char,
+    top: [char; 3],
+    middle: [char; 3],
+    bottom: [char; 3],
+    ellipsis: &'static str,
+}
+
+impl Border {
+    fn chars(self) -> BorderChars {
+        match self {
+            Border::Unicode => BorderChars {
+                horizontal: '─',
+                vertical: '│',
+                top: ['┌', '┬', '┐'],
+                middle: ['├', '┼', '┤'],
+                bottom: ['└', '┴', '┘'],
+                ellipsis: "…",
+            },
+            Border::Ascii => BorderChars {
+                horizontal: '-',
+                vertical: '|',
+                top: ['+', '+', '+'],
+                middle: ['+', '+', '+'],
+                bottom: ['+', '+', '+'],
+                ellipsis: "...",
+            },
+        }
+    }
+}
+
+/// A table of text cells rendered with aligned columns.
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<I, S>(headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            headers: headers.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push_row<I, S>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rows.push(row.into_iter().map(Into::into).collect());
+    }
+
+    pub fn render(&self, style: &TableStyle) -> String {
+        let chars = style.border.chars();
+        let Layout {
+            headers,
+            rows,
+            widths,
+        } = self.layout(style);
+        let numeric: Vec<bool> = (0..widths.len())
+            .map(|i| is_numeric_column(rows.iter().map(|row| row[i].as_str())))
+            .collect();
+
+        let rule = |[left, cross, right]: [char; 3]| {
+            let segments: Vec<String> = widths
+                .iter()
+                .map(|width| chars.horizontal.to_string().repeat(width + 2))
+                .collect();
+            format!("{}{}{}", left, segments.join(&cross.to_string()), right)
+        };
+        let line = |cells: &[String], header: bool| {
+            let cells: Vec<String> = cells
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    let padding = " ".repeat(widths[i] - cell.width());
+                    let cell = if header && style.bold_headers {
+                        format!("\x1b[1m{}\x1b[0m", cell)
+                    } else {
+                        cell.clone()
+                    };
+                    if numeric[i] {
+                        format!(" {}{} ", padding, cell)
+                    } else {
+                        format!(" {}{} ", cell, padding)
+                    }
+                })
+                .collect();
+            let vertical = chars.vertical.to_string();
+            format!("{}{}{}", vertical, cells.join(&vertical), vertical)
+        };
+
+        let mut lines = vec![rule(chars.top), line(&headers, true), rule(chars.middle)];
+        lines.extend(rows.iter().map(|row| line(row, false)));
+        lines.push(rule(chars.bottom));
+        lines.join("\n")
+    }
+
+    /// The length in bytes of [`Table::render`], worked out without building
+    /// the padded lines.
+    pub fn rendered_len(&self, style: &TableStyle) -> usize {
+        let chars = style.border.chars();
+        let Layout {
+            headers,
+            rows,
+            widths,
+        } = self.layout(style);
+
+        let padded: usize = widths.iter().map(|width| width + 2).sum();
+        let rule = |[left, cross, right]: [char; 3]| {
+            left.len_utf8()
+                + padded * chars.horizontal.len_utf8()
+                + widths.len().saturating_sub(1) * cross.len_utf8()
+                + right.len_utf8()
+        };
+        let line = |cells: &[String]| {
+            let cells: usize = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| cell.len() + width - cell.width() + 2)
+                .sum();
+            (widths.len() + 1).max(2) * chars.vertical.len_utf8() + cells
+        };
+        let bold = match style.bold_headers {
+            true => headers.len() * "\x1b[1m\x1b[0m".len(),
+            false => 0,
+        };
+
+        rule(chars.top)
+            + line(&headers)
+            + bold
+            + rule(chars.middle)
+            + rows.iter().map(|row| line(row)).sum::<usize>()
+            + rule(chars.bottom)
+            + rows.len()
+            + 3
+    }
+
+    /// Cells cut to fit `style`, with every row as wide as the widest one.
+    fn layout(&self, style: &TableStyle) -> Layout {
+        let ellipsis = style.border.chars().ellipsis;
+        let columns = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.headers.len()])
+            .max()
+            .unwrap_or(0);
+
+        let fit = |cell: &str| truncate(&sanitize(cell), style.max_column_width, ellipsis);
+        let headers: Vec<String> = (0..columns)
+            .map(|i| fit(self.headers.get(i).map_or("", String::as_str)))
+            .collect();
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                (0..columns)
+                    .map(|i| fit(row.get(i).map_or("", String::as_str)))
+                    .collect()
+            })
+            .collect();
+
+        let widths: Vec<usize> = (0..columns)
+            .map(|i| {
+                rows.iter()
+                    .map(|row| row[i].width())
+                    .chain([headers[i].width()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        Layout {
+            headers,
+            rows,
+            widths,
+        }
+    }
+}
+
+struct Layout {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    widths: Vec<usize>,
+}
+
+/// A column is numeric when it has at least one value and every non-empty
+/// value parses as a number.
+pub fn is_numeric_column<'a>(mut cells: impl Iterator<Item = &'a str>) -> bool {
+    let mut seen = false;
+    let all_numeric = cells.all(|cell| {
+        let cell = cell.trim();
+        seen |= !cell.is_empty();
+        cell.is_empty() || cell.parse::<f64>().is_ok()
+    });
+    seen && all_numeric
+}
+
+/// Replaces line breaks and other control characters so a cell stays on one line.
+fn sanitize(cell: &str) -> String {
+    cell.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+/// Shortens `cell` to at most `max_width` display columns, ending it with
+/// `ellipsis` when anything was cut off. A `max_width` of `0` keeps it whole.
+fn truncate(cell: &str, max_width: usize, ellipsis: &str) -> String {
+    if max_width == 0 || cell.width() <= max_width {
+        return cell.to_string();
+    }
+
+    let budget = max_width.saturating_sub(ellipsis.width());
+    let mut width = 0;
+    let mut truncated = String::new();
+    for c in cell.chars() {
+        width += c.width().unwrap_or(0);
+        if width > budget {
+            break;
+        }
+        truncated.push(c);
+    }
+    truncated.push_str(ellipsis);
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(border: Border, max_column_width: usize) -> TableStyle {
+        TableStyle {
+            border,
+            max_column_width,
+            bold_headers: false,
+        }
+    }
+
+    #[test]
+    fn test_render_aligns_columns_and_numbers() {
+        let mut table = Table::new(["Name", "Age"]);
+        table.push_row(["Jo", "7"]);
+        table.push_row(["Mario", "45"]);
+
+        let expected = "\
++-------+-----+
+| Name  | Age |
++-------+-----+
+| Jo    |   7 |
+| Mario |  45 |
++-------+-----+";
+        assert_eq!(table.render(&plain(Border::Ascii, 32)), expected);
+    }
+
+    #[test]
+    fn test_render_uses_display_width_for_wide_characters() {
+        let mut table = Table::new(["City"]);
+        table.push_row(["東京"]);
+        table.push_row(["Rome"]);
+
+        let expected = "\
+┌──────┐
+│ City │
+├──────┤
+│ 東京 │
+│ Rome │
+└──────┘";
+        assert_eq!(table.render(&plain(Border::Unicode, 32)), expected);
+    }
+
+    #[test]
+    fn test_rendered_len_matches_render() {
+        let mut table = Table::new(["City", "Population", ""]);
+        table.push_row(["東京", "37400068"]);
+        table.push_row(["A very long city name that needs truncating", "1", "x\ty"]);
+
+        for border in [Border::Unicode, Border::Ascii] {
+            for (max_column_width, bold_headers) in [(32, false), (0, true), (5, true)] {
+                let style = TableStyle {
+                    border,
+                    max_column_width,
+                    bold_headers,
+                };
+                assert_eq!(table.rendered_len(&style), table.render(&style).len());
+            }
+        }
+
+        let empty = Table::new(Vec::<String>::new());
+        let style = TableStyle::default();
+        assert_eq!(empty.rendered_len(&style), empty.render(&style).len());
+    }
+
+    #[test]
+    fn test_style_from_args() {
+        let (args, _) = Args::parse("(border=ascii, max-width=0)").unwrap();
+        let style = TableStyle::from_args(&args).unwrap();
+
+        assert_eq!(style.border, Border::Ascii);
+        assert_eq!(style.max_column_width, 0);
+        assert!(!style.bold_headers);
+        assert_eq!(
+            TableStyle::from_args(&Args::default())
+                .unwrap()
+                .max_column_width,
+            32
+        );
+    }
+
+    #[test]
+    fn test_truncate_long_cells() {
+        assert_eq!(truncate("Hello World", 8, "…"), "Hello W…");
+        assert_eq!(truncate("Hello World", 8, "..."), "Hello...");
+        assert_eq!(truncate("東京都庁", 5, "…"), "東京…");
+        assert_eq!(truncate("short", 8, "…"), "short");
+        assert_eq!(truncate("Hello World", 0, "…"), "Hello World");
+    }
+}
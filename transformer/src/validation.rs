@@ -0,0 +1,233 @@
+use crate::error::TransformError;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How the length of an input is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthUnit {
+    Bytes,
+    Chars,
+    Graphemes,
+}
+
+impl LengthUnit {
+    fn measure(self, input: &str) -> usize {
+        match self {
+            LengthUnit::Bytes => input.len(),
+            LengthUnit::Chars => input.chars().count(),
+            LengthUnit::Graphemes => input.graphemes(true).count(),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            LengthUnit::Bytes => "bytes",
+            LengthUnit::Chars => "characters",
+            LengthUnit::Graphemes => "graphemes",
+        }
+    }
+}
+
+/// Rules the input of a pipeline is checked against before its first stage
+/// runs, and a limit on what every stage may produce.
+///
+/// Limits of `0` mean "no limit". The defaults match the historical
+/// behaviour: at most 100 bytes, any content allowed, any output.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ValidationPolicy {
+    pub max_length: usize,
+    pub unit: LengthUnit,
+    /// Per-operation overrides of `max_length`, keyed by operation name.
+    pub limits: HashMap<String, usize>,
+    /// Rejects control characters other than tabs and line breaks.
+    pub reject_control_chars: bool,
+    /// Rejects inputs made only of whitespace.
+    pub require_non_whitespace: bool,
+    /// Maximum length in bytes of the output of each stage. Operations that
+    /// can produce far more than their input are refused before they build
+    /// such an output; see
+    /// [`Operation::output_bound`](crate::Operation::output_bound).
+    pub max_output: usize,
+}
+
+impl Default for ValidationPolicy {
+    fn default() -> Self {
+        Self {
+            max_length: 100,
+            unit: LengthUnit::Bytes,
+            limits: HashMap::new(),
+            reject_control_chars: false,
+            require_non_whitespace: false,
+            max_output: 0,
+        }
+    }
+}
+
+impl ValidationPolicy {
+    /// Applies a command-line flag, taking its value from `args` if it needs
+    /// one. Returns `Ok(false)` when `flag` is not a validation flag.
+    pub fn apply_flag(
+        &mut self,
+        flag: &str,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<bool, Box<dyn Error>> {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {}", flag))
+        };
+
+        match flag {
+            "--max-length" => self.max_length = parse_length(&value()?)?,
+            "--no-limit" => {
+                self.max_length = 0;
+                self.limits.clear();
+            }
+            "--limit" => {
+                let value = value()?;
+                let (operation, limit) = value.split_once('=').ok_or_else(|| {
+                    format!("Expected --limit <operation>=<length>, got '{}'", value)
+                })?;
+                self.limits
+                    .insert(operation.to_string(), parse_length(limit)?);
+            }
+            "--count" => {
+                self.unit = match value()?.as_str() {
+                    "bytes" => LengthUnit::Bytes,
+                    "chars" => LengthUnit::Chars,
+                    "graphemes" => LengthUnit::Graphemes,
+                    other => {
+                        return Err(format!(
+                            "Unknown length unit '{}' (expected bytes, chars or graphemes)",
+                            other
+                        )
+                        .into())
+                    }
+                }
+            }
+            "--reject-control" => self.reject_control_chars = true,
+            "--require-non-whitespace" => self.require_non_whitespace = true,
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    pub fn validate_input(&self, operation: &str, input: &str) -> Result<(), TransformError> {
+        if input.is_empty() {
+            return Err(TransformError::Validation("String is empty".to_string()));
+        }
+
+        let max_length = self
+            .limits
+            .get(operation)
+            .copied()
+            .unwrap_or(self.max_length);
+        if max_length > 0 && self.unit.measure(input) > max_length {
+            return Err(TransformError::Validation(format!(
+                "String is too long (max {} {})",
+                max_length,
+                self.unit.label()
+            )));
+        }
+
+        if self.require_non_whitespace && input.trim().is_empty() {
+            return Err(TransformError::Validation(
+                "String contains only whitespace".to_string(),
+            ));
+        }
+
+        if self.reject_control_chars {
+            if let Some((offset, c)) = input
+                .char_indices()
+                .find(|(_, c)| c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+            {
+                return Err(TransformError::Validation(format!(
+                    "String contains control character U+{:04X} at byte offset {}",
+                    c as u32, offset
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the length in bytes of an output, or of an upper bound on it,
+    /// against `max_output`.
+    pub fn validate_output(&self, length: usize) -> Result<(), TransformError> {
+        match self.max_output {
+            0 => Ok(()),
+            limit if length > limit => Err(TransformError::OutputTooLarge { limit }),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn parse_length(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid length '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(flags: &[&str]) -> ValidationPolicy {
+        let mut policy = ValidationPolicy::default();
+        let mut args = flags.iter().map(|flag| flag.to_string());
+        while let Some(flag) = args.next() {
+            assert!(policy.apply_flag(&flag, &mut args).unwrap(), "{}", flag);
+        }
+        policy
+    }
+
+    #[test]
+    fn test_default_policy_keeps_byte_limit() {
+        let policy = ValidationPolicy::default();
+
+        assert!(policy.validate_input("lowercase", &"a".repeat(100)).is_ok());
+        assert_eq!(
+            policy
+                .validate_input("lowercase", &"a".repeat(101))
+                .unwrap_err()
+                .to_string(),
+            "String is too long (max 100 bytes)"
+        );
+        assert!(policy.validate_input("lowercase", "").is_err());
+    }
+
+    #[test]
+    fn test_units_and_per_operation_limits() {
+        let policy = policy(&[
+            "--max-length",
+            "3",
+            "--count",
+            "graphemes",
+            "--limit",
+            "csv=0",
+        ]);
+
+        assert!(policy.validate_input("reverse", "🇨🇿🇨🇿🇨🇿").is_ok());
+        assert!(policy.validate_input("reverse", "abcd").is_err());
+        assert!(policy.validate_input("csv", &"a".repeat(1000)).is_ok());
+    }
+
+    #[test]
+    fn test_content_rules() {
+        let policy = policy(&["--no-limit", "--reject-control", "--require-non-whitespace"]);
+
+        assert!(policy.validate_input("wrap", "line\nline\t").is_ok());
+        assert!(policy.validate_input("wrap", " \n ").is_err());
+        assert_eq!(
+            policy
+                .validate_input("wrap", "ab\u{7}")
+                .unwrap_err()
+                .to_string(),
+            "String contains control character U+0007 at byte offset 2"
+        );
+    }
+}